@@ -0,0 +1,82 @@
+// Throughput of a sync batch under load: page writes that block like `exec_batch` run next to
+// many in-flight fetches. `inline` blocks the runtime workers the way the api tasks used to,
+// `offloaded` goes through `share::offload::offload`, the body of `share::common::blocking`, with
+// a semaphore of one permit per pooled connection standing in for `budget::db`.
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+use tokio::runtime::{Builder, Runtime};
+use tokio::sync::Semaphore;
+use tokio::time::sleep;
+
+#[path = "../src/share/offload.rs"]
+mod offload;
+
+use offload::offload;
+
+const WORKERS: usize = 4;
+const POOL: usize = 8;
+const WRITES: usize = 64;
+const FETCHES: usize = 256;
+const WRITE_COST: Duration = Duration::from_millis(2);
+const FETCH_LATENCY: Duration = Duration::from_millis(1);
+
+#[derive(Clone, Copy)]
+enum Mode {
+    Inline,
+    Offloaded,
+}
+
+fn runtime() -> Runtime {
+    Builder::new_multi_thread()
+        .worker_threads(WORKERS)
+        .enable_time()
+        .build()
+        .unwrap()
+}
+
+async fn write(mode: Mode, permits: Arc<Semaphore>) {
+    match mode {
+        Mode::Inline => thread::sleep(WRITE_COST),
+        Mode::Offloaded => {
+            let permit = async move { permits.acquire_owned().await.unwrap() };
+            offload(permit, || thread::sleep(WRITE_COST)).await;
+        }
+    }
+}
+
+async fn batch(mode: Mode) {
+    let permits = Arc::new(Semaphore::new(POOL));
+    let mut handles = Vec::with_capacity(WRITES + FETCHES);
+    for _ in 0..WRITES {
+        let permits = Arc::clone(&permits);
+        handles.push(tokio::spawn(write(mode, permits)));
+    }
+    for _ in 0..FETCHES {
+        handles.push(tokio::spawn(async {
+            for _ in 0..4 {
+                sleep(FETCH_LATENCY).await;
+            }
+        }));
+    }
+    for handle in handles {
+        handle.await.unwrap();
+    }
+}
+
+fn bench(c: &mut Criterion) {
+    let rt = runtime();
+    let mut group = c.benchmark_group("sync_under_load");
+    group.sample_size(20);
+    group.throughput(Throughput::Elements((WRITES + FETCHES) as u64));
+    for (name, mode) in [("inline", Mode::Inline), ("offloaded", Mode::Offloaded)] {
+        group.bench_with_input(BenchmarkId::from_parameter(name), &mode, |b, mode| {
+            b.to_async(&rt).iter(|| batch(*mode));
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench);
+criterion_main!(benches);
@@ -0,0 +1,140 @@
+// Rows per second for a 500-row report page of 400 numeric columns. `per_row` is how
+// `report_api` used to write a page, `exec_batch` with one statement per row, `multi_row` is
+// `share::upsert::exec`: the same `Template` chunking the page under the default `[sink]` budgets
+// inside one transaction.
+//
+// Needs a scratch database, e.g.
+// MAV_BENCH_MYSQL_URL=mysql://root@127.0.0.1:4000/test cargo bench --bench upsert
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use mysql::prelude::Queryable;
+use mysql::{Params, Pool, TxOpts, Value};
+
+#[allow(dead_code)]
+#[path = "../src/share/template.rs"]
+mod template;
+
+use template::Template;
+
+const TABLE: &str = "bench_report_upsert";
+const ROWS: usize = 500;
+const COLUMNS: usize = 400;
+const MAX_PLACEHOLDERS: usize = 65535;
+const MAX_PACKET_BYTES: usize = 4 << 20;
+
+#[derive(Clone, Copy)]
+enum Mode {
+    PerRow,
+    MultiRow,
+}
+
+fn columns() -> Vec<String> {
+    (0..COLUMNS).map(|i| format!("c{}", i)).collect()
+}
+
+fn setup(pool: &Pool) {
+    let mut con = pool.get_conn().unwrap();
+    con.query_drop(format!("DROP TABLE IF EXISTS `{}`", TABLE))
+        .unwrap();
+    let columns = columns()
+        .iter()
+        .map(|c| format!("`{}` DOUBLE", c))
+        .collect::<Vec<_>>()
+        .join(", ");
+    con.query_drop(format!(
+        "CREATE TABLE `{}` (`id` BIGINT PRIMARY KEY, {}, `sync_time` DATETIME)",
+        TABLE, columns
+    ))
+    .unwrap();
+}
+
+fn rows(round: u64) -> Vec<Vec<Value>> {
+    (0..ROWS as i64)
+        .map(|id| {
+            let mut row = vec![Value::Int(id)];
+            row.extend((0..COLUMNS).map(|c| Value::Double((round as usize * c) as f64 / 7.0)));
+            row
+        })
+        .collect()
+}
+
+// The single-row upsert with named params, the way the report sql is written.
+fn sql() -> String {
+    let columns = columns();
+    format!(
+        "INSERT INTO `{}` (`id`, {}, `sync_time`) VALUES (:id, {}, NOW()) ON DUPLICATE KEY UPDATE {}, `sync_time` = NOW()",
+        TABLE,
+        columns
+            .iter()
+            .map(|c| format!("`{}`", c))
+            .collect::<Vec<_>>()
+            .join(", "),
+        columns
+            .iter()
+            .map(|c| format!(":{}", c))
+            .collect::<Vec<_>>()
+            .join(", "),
+        columns
+            .iter()
+            .map(|c| format!("`{0}` = :{0}", c))
+            .collect::<Vec<_>>()
+            .join(", ")
+    )
+}
+
+fn params(row: Vec<Value>) -> Params {
+    let names = std::iter::once(String::from("id")).chain(columns());
+    Params::from(names.zip(row).collect::<Vec<_>>())
+}
+
+fn write(pool: &Pool, mode: Mode, sql: &str, template: &Template, rows: Vec<Vec<Value>>) {
+    let mut con = pool.get_conn().unwrap();
+    match mode {
+        Mode::PerRow => con.exec_batch(sql, rows.into_iter().map(params)).unwrap(),
+        Mode::MultiRow => {
+            let rows = rows
+                .into_iter()
+                .map(|row| template.values(params(row)).unwrap())
+                .collect();
+            let mut tx = con.start_transaction(TxOpts::default()).unwrap();
+            for (count, values) in template.chunks(rows, MAX_PLACEHOLDERS, MAX_PACKET_BYTES) {
+                tx.exec_drop(template.statement(count), values).unwrap();
+            }
+            tx.commit().unwrap();
+        }
+    }
+}
+
+fn bench(c: &mut Criterion) {
+    let Ok(url) = std::env::var("MAV_BENCH_MYSQL_URL") else {
+        eprintln!("MAV_BENCH_MYSQL_URL Not Set, Skipping Upsert Bench");
+        return;
+    };
+    let pool = Pool::new(url.as_str()).unwrap();
+    setup(&pool);
+    let sql = sql();
+    let template = Template::parse(&sql).unwrap();
+    let mut group = c.benchmark_group("report_page_upsert");
+    group.sample_size(10);
+    group.throughput(Throughput::Elements(ROWS as u64));
+    for (name, mode) in [("per_row", Mode::PerRow), ("multi_row", Mode::MultiRow)] {
+        let mut round = 0;
+        group.bench_with_input(BenchmarkId::from_parameter(name), &mode, |b, mode| {
+            b.iter_batched(
+                || {
+                    round += 1;
+                    rows(round)
+                },
+                |rows| write(&pool, *mode, &sql, &template, rows),
+                criterion::BatchSize::PerIteration,
+            );
+        });
+    }
+    group.finish();
+    pool.get_conn()
+        .unwrap()
+        .query_drop(format!("DROP TABLE `{}`", TABLE))
+        .unwrap();
+}
+
+criterion_group!(benches, bench);
+criterion_main!(benches);
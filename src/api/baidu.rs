@@ -0,0 +1,360 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::api::handler::Registry;
+use crate::api::platform::{CodeClass, MediaPlatform};
+use crate::model::adgroup::BaiduAdgroup;
+use crate::model::advertiser::BaiduAccount;
+use crate::model::base::{ApiRes, BaiduReportData, BaiduReq, BaiduReqHeader, BaiduRes};
+use crate::model::campaign::BaiduCampaign;
+use crate::model::category::Category;
+use crate::model::creative::BaiduCreative;
+use crate::model::error::{Error, Result};
+use crate::model::keyword::BaiduKeyword;
+use crate::model::message::Verify;
+use crate::model::report::BaiduReport;
+use crate::share::budget;
+use crate::share::common::{
+    BAIDU_ACCOUNT_FIELDS, BAIDU_ADGROUP_FIELDS, BAIDU_CAMPAIGN_FIELDS, BAIDU_CREATIVE_FIELDS,
+    BAIDU_KEYWORD_FIELDS, HTTP_CLIENT, Limiter, Tracker, fetch_pages, tasks_handle, until_ready,
+    verify_rt,
+};
+use crate::share::limiter::endpoint_limiter;
+use crate::share::sink::{self, Row};
+use crate::share::token::account_token;
+use mysql::*;
+use reqwest::{RequestBuilder, Response};
+use retry_macro::retry;
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value, json};
+
+const PAGE_SIZE: u64 = 1000;
+
+pub struct Baidu;
+
+impl MediaPlatform for Baidu {
+    fn media_id(&self) -> u8 {
+        1
+    }
+
+    // The token travels in the request envelope header, see `request`.
+    fn authorize(&self, req: RequestBuilder, _token: &str) -> RequestBuilder {
+        req
+    }
+
+    fn classify(&self, code: i32) -> CodeClass {
+        match code {
+            8206..=8208 => CodeClass::Token,
+            8904 | 9018 => CodeClass::Throttle,
+            _ => CodeClass::Other,
+        }
+    }
+
+    fn backoff(&self, class: CodeClass) -> Duration {
+        match class {
+            CodeClass::Token => Duration::from_millis(1500),
+            _ => Duration::from_millis(1000),
+        }
+    }
+
+    fn register(&self, registry: &mut Registry) {
+        let media_id = self.media_id();
+        registry.register(media_id, Category::Account, |items| {
+            Box::pin(entity_sync::<BaiduAccount>(
+                items,
+                ("accountFields", BAIDU_ACCOUNT_FIELDS),
+            ))
+        });
+        registry.register(media_id, Category::Campaign, |items| {
+            Box::pin(entity_sync::<BaiduCampaign>(
+                items,
+                ("campaignFields", BAIDU_CAMPAIGN_FIELDS),
+            ))
+        });
+        registry.register(media_id, Category::Adgroup, |items| {
+            Box::pin(entity_sync::<BaiduAdgroup>(
+                items,
+                ("adgroupFields", BAIDU_ADGROUP_FIELDS),
+            ))
+        });
+        registry.register(media_id, Category::Keyword, |items| {
+            Box::pin(entity_sync::<BaiduKeyword>(
+                items,
+                ("wordFields", BAIDU_KEYWORD_FIELDS),
+            ))
+        });
+        registry.register(media_id, Category::Creative, |items| {
+            Box::pin(entity_sync::<BaiduCreative>(
+                items,
+                ("creativeFields", BAIDU_CREATIVE_FIELDS),
+            ))
+        });
+        registry.register(media_id, Category::AccountDailyReport, |items| {
+            Box::pin(report_sync(items))
+        });
+        registry.register(media_id, Category::KeywordDailyReport, |items| {
+            Box::pin(report_sync(items))
+        });
+    }
+
+    async fn envelope<T: DeserializeOwned>(&self, res: Response) -> Result<ApiRes<T>> {
+        Ok(res.json::<BaiduRes<T>>().await?.into())
+    }
+}
+
+// `userName` in the verify body selects the target account and moves into the envelope header.
+fn request(verify: &Verify, token: &str, extra: Map<String, Value>) -> BaiduReq<Value> {
+    let mut body = match &verify.body {
+        Some(Value::Object(map)) => map.clone(),
+        _ => Map::new(),
+    };
+    let user_name = body
+        .remove("userName")
+        .and_then(|name| name.as_str().map(String::from));
+    for (key, value) in extra {
+        body.entry(key).or_insert(value);
+    }
+    BaiduReq {
+        header: BaiduReqHeader {
+            access_token: token.to_string(),
+            user_name,
+        },
+        body: Value::Object(body),
+    }
+}
+
+#[retry]
+async fn entity_api<T: DeserializeOwned + Row + Send + 'static>(
+    verify: Verify,
+    fields: (&'static str, &'static [&'static str]),
+    limiter: &Limiter,
+    tracker: &Tracker,
+) -> Result<()> {
+    tracker.attempt();
+    let url = &verify.url;
+    let account_id = verify.account_id;
+    let media_id = verify.media_id;
+    let mut token = account_token(account_id, media_id).await?;
+    let mut extra = Map::new();
+    extra.insert(fields.0.to_string(), json!(fields.1));
+    until_ready(limiter).await;
+    let res = Baidu
+        .authorize(HTTP_CLIENT.post(url), &token)
+        .json(&request(&verify, &token, extra))
+        .send()
+        .await?;
+    let res_parsed = Baidu.envelope::<Vec<T>>(res).await?;
+    match res_parsed {
+        ApiRes {
+            code: 0,
+            data: Some(items),
+            ..
+        } => {
+            if !items.is_empty() {
+                sink::write(&verify, items).await?;
+            }
+            Ok(())
+        }
+        ApiRes { code, message, .. } => {
+            Baidu.wait(code, &mut token, account_id, limiter).await;
+            Err(Error::Api {
+                code,
+                message: message.unwrap_or_default(),
+                request_id: None,
+            })
+        }
+    }
+}
+
+async fn entity_sync<T: DeserializeOwned + Row + Send + 'static>(
+    verifies: Vec<Verify>,
+    fields: (&'static str, &'static [&'static str]),
+) {
+    let mut handles = vec![];
+    for verify in verifies.into_iter() {
+        let limiter = endpoint_limiter(&verify);
+        handles.push(
+            budget::spawn(verify.media_id, verify.cate, async move {
+                let tracker = Tracker::new();
+                let rt = entity_api::<T>(verify.clone(), fields, &limiter, &tracker).await;
+                let _rt = verify_rt(&verify, rt, &tracker).await;
+            })
+            .await,
+        );
+    }
+    tasks_handle(handles).await;
+}
+
+#[retry]
+async fn report_api(
+    verify: &Verify,
+    mut token: String,
+    page: u64,
+    limiter: &Limiter,
+    tracker: &Tracker,
+) -> Result<u64> {
+    tracker.attempt();
+    let account_id = verify.account_id;
+    let mut extra = Map::new();
+    extra.insert(String::from("startRow"), json!((page - 1) * PAGE_SIZE));
+    extra.insert(String::from("rowCount"), json!(PAGE_SIZE));
+    until_ready(limiter).await;
+    let res = Baidu
+        .authorize(HTTP_CLIENT.post(&verify.url), &token)
+        .json(&request(verify, &token, extra))
+        .send()
+        .await?;
+    let res_parsed = Baidu
+        .envelope::<Vec<BaiduReportData<BaiduReport>>>(res)
+        .await?;
+    match res_parsed {
+        ApiRes {
+            code: 0,
+            data: Some(data),
+            ..
+        } => {
+            let Some(BaiduReportData {
+                total_row_count,
+                rows,
+            }) = data.into_iter().next()
+            else {
+                return Ok(0);
+            };
+            let items = rows.unwrap_or_default();
+            if !items.is_empty() {
+                let rows = items.len();
+                sink::write(verify, items).await?;
+                tracker.rows_written(rows);
+            }
+            Ok(total_row_count.unwrap_or_default().div_ceil(PAGE_SIZE))
+        }
+        ApiRes { code, message, .. } => {
+            Baidu.wait(code, &mut token, account_id, limiter).await;
+            Err(Error::Api {
+                code,
+                message: message.unwrap_or_default(),
+                request_id: None,
+            })
+        }
+    }
+}
+
+async fn report_sync(verifies: Vec<Verify>) {
+    let mut handles = vec![];
+    for verify in verifies {
+        let tracker = Arc::new(Tracker::new());
+        let limiter = endpoint_limiter(&verify);
+        handles.push(
+            budget::spawn(verify.media_id, verify.cate, async move {
+                let rt = fetch_pages(&verify, &tracker, |pg| {
+                    let verify = verify.clone();
+                    let limiter = Arc::clone(&limiter);
+                    let tracker = Arc::clone(&tracker);
+                    // Read per page, so a token refreshed after an auth error reaches the later pages.
+                    async move {
+                        let token = account_token(verify.account_id, verify.media_id).await?;
+                        report_api(&verify, token, pg, &limiter, &tracker).await
+                    }
+                })
+                .await;
+                let _rt = verify_rt(&verify, rt, &tracker).await;
+            })
+            .await,
+        );
+    }
+    tasks_handle(handles).await;
+}
+
+impl Row for BaiduAccount {
+    fn params(&self, _verify: &Verify) -> Params {
+        params! {
+            "user_id" => &self.user_id,
+            "user_name" => &self.user_name,
+            "balance" => &self.balance,
+            "budget" => &self.budget,
+            "budget_type" => &self.budget_type,
+            "user_stat" => &self.user_stat,
+        }
+    }
+}
+
+impl Row for BaiduCampaign {
+    fn params(&self, verify: &Verify) -> Params {
+        params! {
+            "user_id" => verify.account_id,
+            "campaign_id" => &self.campaign_id,
+            "campaign_name" => &self.campaign_name,
+            "budget" => &self.budget,
+            "pause" => &self.pause,
+            "status" => &self.status,
+        }
+    }
+}
+
+impl Row for BaiduAdgroup {
+    fn params(&self, verify: &Verify) -> Params {
+        params! {
+            "user_id" => verify.account_id,
+            "adgroup_id" => &self.adgroup_id,
+            "campaign_id" => &self.campaign_id,
+            "adgroup_name" => &self.adgroup_name,
+            "max_price" => &self.max_price,
+            "pause" => &self.pause,
+            "status" => &self.status,
+        }
+    }
+}
+
+impl Row for BaiduKeyword {
+    fn params(&self, verify: &Verify) -> Params {
+        params! {
+            "user_id" => verify.account_id,
+            "keyword_id" => &self.keyword_id,
+            "adgroup_id" => &self.adgroup_id,
+            "campaign_id" => &self.campaign_id,
+            "keyword" => &self.keyword,
+            "price" => &self.price,
+            "match_type" => &self.match_type,
+            "pause" => &self.pause,
+            "status" => &self.status,
+        }
+    }
+}
+
+impl Row for BaiduCreative {
+    fn params(&self, verify: &Verify) -> Params {
+        params! {
+            "user_id" => verify.account_id,
+            "creative_id" => &self.creative_id,
+            "adgroup_id" => &self.adgroup_id,
+            "campaign_id" => &self.campaign_id,
+            "title" => &self.title,
+            "description1" => &self.description1,
+            "description2" => &self.description2,
+            "pc_destination_url" => &self.pc_destination_url,
+            "mobile_destination_url" => &self.mobile_destination_url,
+            "pause" => &self.pause,
+            "status" => &self.status,
+        }
+    }
+}
+
+// Covers both report tables, each upsert reads the params it names.
+impl Row for BaiduReport {
+    fn params(&self, verify: &Verify) -> Params {
+        params! {
+            "user_id" => verify.account_id,
+            "date" => &self.date,
+            "campaign_id" => &self.campaign_id,
+            "adgroup_id" => &self.adgroup_id,
+            "keyword_id" => &self.keyword_id,
+            "keyword" => &self.keyword,
+            "impression" => &self.impression,
+            "click" => &self.click,
+            "cost" => &self.cost,
+            "ctr" => &self.ctr,
+            "cpc" => &self.cpc,
+            "cpm" => &self.cpm,
+        }
+    }
+}
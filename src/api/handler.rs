@@ -0,0 +1,55 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::LazyLock;
+
+use crate::api::platform::PLATFORMS;
+use crate::model::category::Category;
+use crate::model::message::Verify;
+
+pub type BoxFuture<T> = Pin<Box<dyn Future<Output = T> + Send>>;
+
+pub trait SyncHandler: Send + Sync {
+    fn sync(&self, items: Vec<Verify>) -> BoxFuture<()>;
+}
+
+impl<F> SyncHandler for F
+where
+    F: Fn(Vec<Verify>) -> BoxFuture<()> + Send + Sync,
+{
+    fn sync(&self, items: Vec<Verify>) -> BoxFuture<()> {
+        self(items)
+    }
+}
+
+pub static REGISTRY: LazyLock<Registry> = LazyLock::new(|| {
+    let mut registry = Registry::default();
+    for platform in PLATFORMS {
+        platform.register(&mut registry);
+    }
+    registry
+});
+
+#[derive(Default)]
+pub struct Registry {
+    handlers: HashMap<(u8, Category), Box<dyn SyncHandler>>,
+}
+
+impl Registry {
+    pub fn register<F>(&mut self, media_id: u8, cate: Category, handler: F)
+    where
+        F: Fn(Vec<Verify>) -> BoxFuture<()> + Send + Sync + 'static,
+    {
+        if self
+            .handlers
+            .insert((media_id, cate), Box::new(handler))
+            .is_some()
+        {
+            panic!("Duplicate Handler For {}:{}", media_id, cate);
+        }
+    }
+
+    pub fn get(&self, media_id: u8, cate: Category) -> Option<&dyn SyncHandler> {
+        self.handlers.get(&(media_id, cate)).map(|h| h.as_ref())
+    }
+}
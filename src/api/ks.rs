@@ -0,0 +1,319 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::api::handler::Registry;
+use crate::api::platform::{CodeClass, MediaPlatform};
+use crate::model::balance::KsBalance;
+use crate::model::base::{ApiRes, KsPage};
+use crate::model::campaign::KsCampaign;
+use crate::model::category::Category;
+use crate::model::creative::KsCreative;
+use crate::model::error::{Error, Result};
+use crate::model::message::Verify;
+use crate::model::report::KsReport;
+use crate::model::unit::KsUnit;
+use crate::share::budget;
+use crate::share::common::{
+    HTTP_CLIENT, Limiter, Tracker, fetch_pages, tasks_handle, until_ready, verify_rt,
+};
+use crate::share::limiter::endpoint_limiter;
+use crate::share::sink::{self, Row};
+use crate::share::token::account_token;
+use mysql::*;
+use reqwest::RequestBuilder;
+use reqwest::header::HeaderMap;
+use retry_macro::retry;
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value, json};
+
+const PAGE_SIZE: u64 = 500;
+
+pub struct Ks;
+
+impl MediaPlatform for Ks {
+    fn media_id(&self) -> u8 {
+        5
+    }
+
+    fn authorize(&self, req: RequestBuilder, token: &str) -> RequestBuilder {
+        let mut headers = HeaderMap::new();
+        headers.insert("Access-Token", token.parse().unwrap());
+        req.headers(headers)
+    }
+
+    fn classify(&self, code: i32) -> CodeClass {
+        match code {
+            401000..=401003 => CodeClass::Token,
+            402000..=402004 => CodeClass::Throttle,
+            _ => CodeClass::Other,
+        }
+    }
+
+    fn backoff(&self, class: CodeClass) -> Duration {
+        match class {
+            CodeClass::Token => Duration::from_millis(1500),
+            _ => Duration::from_millis(1000),
+        }
+    }
+
+    fn register(&self, registry: &mut Registry) {
+        let media_id = self.media_id();
+        registry.register(media_id, Category::Campaign, |items| {
+            Box::pin(page_sync::<KsCampaign>(items))
+        });
+        registry.register(media_id, Category::Unit, |items| {
+            Box::pin(page_sync::<KsUnit>(items))
+        });
+        registry.register(media_id, Category::Creative, |items| {
+            Box::pin(page_sync::<KsCreative>(items))
+        });
+        registry.register(media_id, Category::AccountHourlyReport, |items| {
+            Box::pin(page_sync::<KsReport>(items))
+        });
+        registry.register(media_id, Category::UnitHourlyReport, |items| {
+            Box::pin(page_sync::<KsReport>(items))
+        });
+        registry.register(media_id, Category::Balance, |items| {
+            Box::pin(balance_sync(items))
+        });
+    }
+}
+
+fn request_body(verify: &Verify, page: u64) -> Value {
+    let mut body = match &verify.body {
+        Some(Value::Object(map)) => map.clone(),
+        _ => Map::new(),
+    };
+    body.insert(String::from("advertiser_id"), json!(verify.account_id));
+    body.insert(String::from("page"), json!(page));
+    body.insert(String::from("page_size"), json!(PAGE_SIZE));
+    Value::Object(body)
+}
+
+#[retry]
+async fn page_api<T: DeserializeOwned + Row + Send + 'static>(
+    verify: &Verify,
+    mut token: String,
+    page: u64,
+    limiter: &Limiter,
+    tracker: &Tracker,
+) -> Result<u64> {
+    tracker.attempt();
+    let account_id = verify.account_id;
+    until_ready(limiter).await;
+    let res = Ks
+        .authorize(HTTP_CLIENT.post(&verify.url), &token)
+        .json(&request_body(verify, page))
+        .send()
+        .await?;
+    let res_parsed = Ks.envelope::<KsPage<T>>(res).await?;
+    match res_parsed {
+        ApiRes {
+            code: 0,
+            data: Some(KsPage {
+                total_count,
+                details,
+            }),
+            ..
+        } => {
+            let items = details.unwrap_or_default();
+            if !items.is_empty() {
+                let rows = items.len();
+                sink::write(verify, items).await?;
+                tracker.rows_written(rows);
+            }
+            Ok(total_count.unwrap_or_default().div_ceil(PAGE_SIZE))
+        }
+        ApiRes {
+            code,
+            message,
+            request_id,
+            ..
+        } => {
+            Ks.wait(code, &mut token, account_id, limiter).await;
+            Err(Error::Api {
+                code,
+                message: message.unwrap_or_default(),
+                request_id,
+            })
+        }
+    }
+}
+
+async fn page_sync<T: DeserializeOwned + Row + Send + 'static>(verifies: Vec<Verify>) {
+    let mut handles = vec![];
+    for verify in verifies {
+        let tracker = Arc::new(Tracker::new());
+        let limiter = endpoint_limiter(&verify);
+        handles.push(
+            budget::spawn(verify.media_id, verify.cate, async move {
+                let rt = fetch_pages(&verify, &tracker, |pg| {
+                    let verify = verify.clone();
+                    let limiter = Arc::clone(&limiter);
+                    let tracker = Arc::clone(&tracker);
+                    // Read per page, so a token refreshed after an auth error reaches the later pages.
+                    async move {
+                        let token = account_token(verify.account_id, verify.media_id).await?;
+                        page_api::<T>(&verify, token, pg, &limiter, &tracker).await
+                    }
+                })
+                .await;
+                let _rt = verify_rt(&verify, rt, &tracker).await;
+            })
+            .await,
+        );
+    }
+    tasks_handle(handles).await;
+}
+
+impl Row for KsCampaign {
+    fn params(&self, verify: &Verify) -> Params {
+        params! {
+            "advertiser_id" => verify.account_id,
+            "campaign_id" => &self.campaign_id,
+            "campaign_name" => &self.campaign_name,
+            "campaign_type" => &self.campaign_type,
+            "put_status" => &self.put_status,
+            "status" => &self.status,
+            "day_budget" => &self.day_budget,
+            "create_time" => &self.create_time,
+            "update_time" => &self.update_time,
+        }
+    }
+}
+
+impl Row for KsUnit {
+    fn params(&self, verify: &Verify) -> Params {
+        params! {
+            "advertiser_id" => verify.account_id,
+            "unit_id" => &self.unit_id,
+            "campaign_id" => &self.campaign_id,
+            "unit_name" => &self.unit_name,
+            "put_status" => &self.put_status,
+            "status" => &self.status,
+            "bid_type" => &self.bid_type,
+            "bid" => &self.bid,
+            "cpa_bid" => &self.cpa_bid,
+            "ocpx_action_type" => &self.ocpx_action_type,
+            "day_budget" => &self.day_budget,
+            "begin_time" => &self.begin_time,
+            "end_time" => &self.end_time,
+            "create_time" => &self.create_time,
+            "update_time" => &self.update_time,
+        }
+    }
+}
+
+impl Row for KsCreative {
+    fn params(&self, verify: &Verify) -> Params {
+        params! {
+            "advertiser_id" => verify.account_id,
+            "creative_id" => &self.creative_id,
+            "unit_id" => &self.unit_id,
+            "campaign_id" => &self.campaign_id,
+            "creative_name" => &self.creative_name,
+            "put_status" => &self.put_status,
+            "status" => &self.status,
+            "creative_material_type" => &self.creative_material_type,
+            "photo_id" => &self.photo_id,
+            "create_time" => &self.create_time,
+            "update_time" => &self.update_time,
+        }
+    }
+}
+
+// Covers both report tables, each upsert reads the params it names.
+impl Row for KsReport {
+    fn params(&self, verify: &Verify) -> Params {
+        params! {
+            "advertiser_id" => verify.account_id,
+            "date" => &self.stat_date,
+            "hour" => &self.stat_hour,
+            "campaign_id" => &self.campaign_id,
+            "campaign_name" => &self.campaign_name,
+            "unit_id" => &self.unit_id,
+            "unit_name" => &self.unit_name,
+            "charge" => &self.charge,
+            "show" => &self.show,
+            "photo_click" => &self.photo_click,
+            "aclick" => &self.aclick,
+            "bclick" => &self.bclick,
+            "share" => &self.share,
+            "comment" => &self.comment,
+            "likes" => &self.likes,
+            "follow" => &self.follow,
+            "play_3s" => &self.play_3s,
+            "play_5s" => &self.play_5s,
+            "play_end" => &self.play_end,
+            "activation" => &self.activation,
+            "conversion_num" => &self.conversion_num,
+            "event_pay" => &self.event_pay,
+            "event_pay_purchase_amount" => &self.event_pay_purchase_amount,
+        }
+    }
+}
+
+#[retry]
+async fn balance_api(verify: Verify, limiter: &Limiter, tracker: &Tracker) -> Result<()> {
+    tracker.attempt();
+    let url = &verify.url;
+    let account_id = verify.account_id;
+    let media_id = verify.media_id;
+    let mut token = account_token(account_id, media_id).await?;
+    until_ready(limiter).await;
+    let res = Ks
+        .authorize(HTTP_CLIENT.post(url), &token)
+        .json(&json!({ "advertiser_id": account_id }))
+        .send()
+        .await?;
+    let res_parsed = Ks.envelope::<KsBalance>(res).await?;
+    match res_parsed {
+        ApiRes { code: 0, data, .. } => {
+            if let Some(p) = data {
+                sink::write(&verify, vec![p]).await?;
+            }
+            Ok(())
+        }
+        ApiRes {
+            code,
+            message,
+            request_id,
+            ..
+        } => {
+            Ks.wait(code, &mut token, account_id, limiter).await;
+            Err(Error::Api {
+                code,
+                message: message.unwrap_or_default(),
+                request_id,
+            })
+        }
+    }
+}
+
+async fn balance_sync(verifies: Vec<Verify>) {
+    let mut handles = vec![];
+    for verify in verifies.into_iter() {
+        let limiter = endpoint_limiter(&verify);
+        handles.push(
+            budget::spawn(verify.media_id, verify.cate, async move {
+                let tracker = Tracker::new();
+                let rt = balance_api(verify.clone(), &limiter, &tracker).await;
+                let _rt = verify_rt(&verify, rt, &tracker).await;
+            })
+            .await,
+        );
+    }
+    tasks_handle(handles).await;
+}
+
+impl Row for KsBalance {
+    fn params(&self, verify: &Verify) -> Params {
+        params! {
+            "advertiser_id" => verify.account_id,
+            "balance" => &self.balance,
+            "recharge_balance" => &self.recharge_balance,
+            "direct_rebate" => &self.direct_rebate,
+            "contract_rebate" => &self.contract_rebate,
+        }
+    }
+}
@@ -1,3 +1,7 @@
+pub mod baidu;
 pub mod gdt;
+pub mod handler;
+pub mod ks;
+pub mod platform;
 pub mod router;
 pub mod tt;
@@ -0,0 +1,64 @@
+use std::time::Duration;
+
+use crate::api::baidu::Baidu;
+use crate::api::gdt::Gdt;
+use crate::api::handler::Registry;
+use crate::api::ks::Ks;
+use crate::api::tt::Tt;
+use crate::model::base::ApiRes;
+use crate::model::error::Result;
+use crate::share::common::Limiter;
+use crate::share::token::{account_token, invalidate};
+use log::warn;
+use reqwest::{RequestBuilder, Response};
+use serde::de::DeserializeOwned;
+use tokio::time::sleep;
+
+pub static PLATFORMS: [&dyn MediaPlatform; 4] = [&Gdt, &Tt, &Ks, &Baidu];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CodeClass {
+    Token,
+    Throttle,
+    Other,
+}
+
+pub trait MediaPlatform: Send + Sync {
+    fn media_id(&self) -> u8;
+
+    fn authorize(&self, req: RequestBuilder, token: &str) -> RequestBuilder;
+
+    fn classify(&self, code: i32) -> CodeClass;
+
+    fn backoff(&self, class: CodeClass) -> Duration;
+
+    fn register(&self, registry: &mut Registry);
+
+    async fn envelope<T: DeserializeOwned>(&self, res: Response) -> Result<ApiRes<T>>
+    where
+        Self: Sized,
+    {
+        Ok(res.json::<ApiRes<T>>().await?)
+    }
+
+    async fn wait(&self, code: i32, token: &mut String, account_id: u64, limiter: &Limiter)
+    where
+        Self: Sized,
+    {
+        match self.classify(code) {
+            CodeClass::Token => {
+                invalidate(account_id, self.media_id(), token).await;
+                sleep(self.backoff(CodeClass::Token)).await;
+                match account_token(account_id, self.media_id()).await {
+                    Ok(tk) => *token = tk,
+                    Err(err) => warn!("Refresh Token Of {} Failed Reason {}", account_id, err),
+                }
+            }
+            CodeClass::Throttle => {
+                limiter.throttled().await;
+                sleep(self.backoff(CodeClass::Throttle)).await;
+            }
+            CodeClass::Other => {}
+        }
+    }
+}
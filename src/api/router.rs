@@ -1,81 +1,277 @@
+use std::cmp::Reverse;
+use std::time::Instant;
 use std::{collections::HashMap, time::Duration};
 
-use crate::api::gdt as gdt_api;
-use crate::api::tt as tt_api;
+use crate::api::handler::{REGISTRY, Registry};
+use crate::model::category::Category;
 use crate::model::error::Error;
 use crate::model::message::Verify;
-use crate::share::common::REDIS_CLUSTER;
-use log::error;
-use r2d2::PooledConnection;
-use redis::Commands;
-use redis::cluster::ClusterClient;
-use tokio::time::sleep;
-
-const BATCH_SIZE: usize = 1000;
-
-const SLEEP_DURATION: Duration = Duration::from_secs(1);
-
-const REDIS_KEY: &str = "rule_message_thread_rule_verify";
+use crate::share::common::{Tracker, redis, reject, verify_rt};
+use crate::share::config::{SourceKind, config};
+use crate::share::{budget, dlq, shutdown, token};
+use crate::source::VerifySource;
+use crate::source::file;
+use crate::source::redis_list::RedisListSource;
+use crate::source::redis_reliable::{self, RedisReliableSource};
+use crate::source::redis_stream::RedisStreamSource;
+use log::{error, info, warn};
+use tokio::task::JoinHandle;
+use tokio::time::{sleep, timeout};
 
 pub async fn route() -> Result<(), Error> {
-    let mut conn = REDIS_CLUSTER.get()?;
+    let config = config();
+    token::init()?;
+    dlq::spawn_scheduler();
+    budget::spawn_reporter();
+    shutdown::spawn_listener();
+    match config.source.kind {
+        SourceKind::RedisList => {
+            run(
+                RedisListSource::new(&config.queue.verify_key).await?,
+                &REGISTRY,
+            )
+            .await
+        }
+        SourceKind::RedisReliable => {
+            run(
+                RedisReliableSource::new(&config.queue.verify_key, &config.source).await?,
+                &REGISTRY,
+            )
+            .await
+        }
+        SourceKind::RedisStream => {
+            run(RedisStreamSource::new(&config.source).await?, &REGISTRY).await
+        }
+        SourceKind::File => run(file::open(&config.source.path).await?, &REGISTRY).await,
+    }
+}
 
-    loop {
-        let verifies = fetch(&mut conn).await?;
+async fn run(mut source: impl VerifySource, registry: &Registry) -> Result<(), Error> {
+    let queue = &config().queue;
+    let started = Instant::now();
+    let (mut fetched, mut unfinished, mut requeued) = (0, 0, 0);
 
+    while !shutdown::stopping() {
+        // Waiting for room is safe to give up, a fetch is not: popped messages would be lost.
+        let max = tokio::select! {
+            max = budget::room(queue.batch_size) => max,
+            _ = shutdown::stopped() => break,
+        };
+        let verifies = match source.fetch(max).await {
+            Ok(Some(verifies)) => verifies,
+            Ok(None) => {
+                info!("Verify Source Drained");
+                break;
+            }
+            Err(err) => {
+                error!("Fetch Verifies Failed Reason {}", err);
+                sleep(Duration::from_millis(queue.idle_ms)).await;
+                continue;
+            }
+        };
+        match source.depth().await {
+            Ok(Some(depth)) => budget::queue_depth(depth),
+            Ok(None) => {}
+            Err(err) => error!("Queue Depth Failed Reason {}", err),
+        }
         if verifies.is_empty() {
-            sleep(SLEEP_DURATION).await;
+            sleep(Duration::from_millis(queue.idle_ms)).await;
+            continue;
+        }
+        fetched += verifies.len();
+
+        let dispatching = dispatch(verifies, registry);
+        tokio::pin!(dispatching);
+        let finished = tokio::select! {
+            _ = &mut dispatching => true,
+            _ = shutdown::stopped() => {
+                let drain = Duration::from_secs(config().shutdown.drain_secs);
+                timeout(drain, &mut dispatching).await.is_ok()
+            }
+        };
+        if !finished {
+            let aborted = shutdown::abort_tasks().await;
+            warn!("Drain Timed Out, Aborted {} Tasks", aborted);
+        }
+        let pending = shutdown::unfinished();
+        if finished {
+            settle(&mut source, pending).await;
             continue;
         }
+        unfinished = pending.len();
+        let items = pending.into_iter().map(|verify| verify.raw).collect();
+        match source.requeue(items).await {
+            Ok(moved) => requeued = moved,
+            Err(err) => error!("Requeue Unfinished Verifies Failed Reason {}", err),
+        }
+    }
 
-        dispatch(verifies).await?;
+    if let Err(err) = source.close().await {
+        error!("Close Verify Source Failed Reason {}", err);
     }
+    if shutdown::stopping() {
+        info!(
+            "Shutdown After {}s: {} Verifies Fetched, {} Unfinished At The Drain Deadline, {} Requeued, {} Lost",
+            started.elapsed().as_secs(),
+            fetched,
+            unfinished,
+            requeued,
+            unfinished.saturating_sub(requeued)
+        );
+    }
+    Ok(())
 }
 
-async fn fetch(conn: &mut PooledConnection<ClusterClient>) -> Result<Vec<String>, Error> {
-    let mut items = Vec::with_capacity(200);
+// Commits a finished batch. Verifies whose result could not be stored are retried through the
+// delay queue like any failed verify, so one that keeps failing ends in the dead letter queue
+// after `max_attempts`. Without redis they go back to the source, or to the dead letter queue
+// when the source can not take them back.
+async fn settle(source: &mut impl VerifySource, pending: Vec<Verify>) {
+    if !pending.is_empty() {
+        warn!(
+            "{} Verifies Finished Without A Stored Result, Retry Them",
+            pending.len()
+        );
+    }
+    let pending = retry(pending).await;
+    if pending.is_empty() {
+        if let Err(err) = source.commit().await {
+            error!("Commit Verifies Failed Reason {}", err);
+        }
+        return;
+    }
+    let items = pending
+        .into_iter()
+        .map(|verify| verify.raw)
+        .collect::<Vec<_>>();
+    match source.requeue(items.clone()).await {
+        Ok(moved) if moved == items.len() => return,
+        Ok(_) => {}
+        Err(err) => error!("Requeue Unfinished Verifies Failed Reason {}", err),
+    }
+    let rt: Result<(), Error> = async {
+        let mut con = redis().await?;
+        for item in &items {
+            dlq::reject(&mut con, item).await?;
+        }
+        Ok(())
+    }
+    .await;
+    if let Err(err) = rt {
+        error!("Dead Letter {} Verifies Failed Reason {}", items.len(), err);
+    }
+    if let Err(err) = source.commit().await {
+        error!("Commit Verifies Failed Reason {}", err);
+    }
+}
 
-    for _ in 0..BATCH_SIZE {
-        match conn.lpop(REDIS_KEY, None) {
-            Ok(Some(msg)) => items.push(msg),
-            Ok(None) => break,
-            Err(e) => {
-                error!("Redis Error: {}", e);
-                break;
-            }
+// Schedules each verify for its next attempt, returns those that could not be scheduled.
+async fn retry(pending: Vec<Verify>) -> Vec<Verify> {
+    if pending.is_empty() {
+        return pending;
+    }
+    let mut con = match redis().await {
+        Ok(con) => con,
+        Err(err) => {
+            error!("Retry Unfinished Verifies Failed Reason {}", err);
+            return pending;
+        }
+    };
+    let mut left = vec![];
+    for verify in pending {
+        let err = Error::Dispatch(String::from("result not stored"));
+        if let Err(err) = dlq::schedule(&mut con, &verify, &err, vec![]).await {
+            error!("Retry Verify {} Failed Reason {}", verify.id, err);
+            left.push(verify);
+            continue;
+        }
+        if let Err(err) = redis_reliable::ack(&mut con, &verify.raw).await {
+            error!("Ack Verify {} Failed Reason {}", verify.id, err);
         }
     }
+    left
+}
 
-    Ok(items)
+// Every media:category group runs as its own task, so a slow report group does not hold up the
+// others and a panicking handler only fails its own verifies. Groups are started by descending
+// priority, and the concurrency budgets keep serving their waiters in that order.
+async fn dispatch(verifies: Vec<String>, registry: &Registry) {
+    for (verify, err) in join(start(verifies, registry).await).await {
+        let _rt = verify_rt(&verify, Err(err), &Tracker::new()).await;
+    }
 }
 
-async fn dispatch(verifies: Vec<String>) -> Result<(), Error> {
-    let grouped = group(verifies);
+type Group = (u8, Category, Vec<Verify>, JoinHandle<()>);
 
-    for (cate, verifies) in grouped {
-        if let Some((media, cate)) = cate.rsplit_once(':')
-            && media.parse::<u8>().is_ok()
-        {
-            route_by_media(media.parse::<u8>().unwrap(), (cate.to_owned(), verifies)).await?;
+// Spawns one task per group, highest priority first.
+async fn start(verifies: Vec<String>, registry: &Registry) -> Vec<Group> {
+    let mut grouped = group(verifies, registry)
+        .await
+        .into_iter()
+        .collect::<Vec<_>>();
+    grouped.sort_by_key(|((media_id, cate), _)| Reverse(config().priority(*media_id, *cate)));
+
+    let mut handles = Vec::with_capacity(grouped.len());
+    for ((media_id, cate), verifies) in grouped {
+        if let Some(handler) = registry.get(media_id, cate) {
+            shutdown::track(&verifies);
+            let pending = verifies.clone();
+            let handle = tokio::spawn(handler.sync(verifies));
+            shutdown::spawned(handle.abort_handle());
+            handles.push((media_id, cate, pending, handle));
         }
     }
+    handles
+}
 
-    Ok(())
+// Waits for every group. A group whose task panicked fails only the verifies still in flight,
+// the ones it finished already have their result.
+async fn join(handles: Vec<Group>) -> Vec<(Verify, Error)> {
+    let mut failed = vec![];
+    for (media_id, cate, pending, handle) in handles {
+        if let Err(err) = handle.await {
+            let pending = shutdown::in_flight(pending);
+            error!(
+                "Group {}:{} Failed With {} Verifies Unfinished Reason {}",
+                media_id,
+                cate,
+                pending.len(),
+                err
+            );
+            for verify in pending {
+                let err = Error::Dispatch(format!("group {}:{} failed: {}", media_id, cate, err));
+                failed.push((verify, err));
+            }
+        }
+    }
+    failed
 }
 
-fn group(verifies: Vec<String>) -> HashMap<String, Vec<Verify>> {
-    let mut grouped: HashMap<String, Vec<Verify>> = HashMap::new();
+async fn group(verifies: Vec<String>, registry: &Registry) -> HashMap<(u8, Category), Vec<Verify>> {
+    let mut grouped: HashMap<(u8, Category), Vec<Verify>> = HashMap::new();
 
     for msg in verifies {
-        match serde_json::from_str::<Verify>(&msg) {
-            Ok(verify) => {
+        match serde_json::from_str::<Verify>(&msg).map(|verify| Verify {
+            raw: msg.clone(),
+            ..verify
+        }) {
+            Ok(verify) if registry.get(verify.media_id, verify.cate).is_some() => {
                 grouped
-                    .entry(format!("{}:{}", verify.media_id, &verify.cate))
+                    .entry((verify.media_id, verify.cate))
                     .or_default()
                     .push(verify);
             }
-            Err(e) => {
-                error!("Failed To Parse Message: {}", e);
+            Ok(verify) => {
+                let err = Error::Unsupported {
+                    media_id: verify.media_id,
+                    cate: verify.cate,
+                };
+                let _rt = verify_rt(&verify, Err(err), &Tracker::new()).await;
+            }
+            Err(err) => {
+                if let Err(err) = reject(&msg, err.into()).await {
+                    error!("Reject Message Failed Reason {}", err);
+                }
             }
         }
     }
@@ -83,11 +279,114 @@ fn group(verifies: Vec<String>) -> HashMap<String, Vec<Verify>> {
     grouped
 }
 
-async fn route_by_media(media_id: u8, payload: (String, Vec<Verify>)) -> Result<(), Error> {
-    match media_id {
-        2 => gdt_api::sync(payload).await?,
-        4 => tt_api::sync(payload).await?,
-        _ => error!("Media Id Not Yet Supported: {}", media_id),
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::handler::BoxFuture;
+    use crate::source::channel;
+    use std::sync::{Arc, Mutex};
+
+    // Runs share the in-flight set of `shutdown`, one at a time keeps their batches apart.
+    static SERIAL: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+    type Seen = Arc<Mutex<Vec<(u8, String)>>>;
+
+    fn message(id: &str, media_id: u8, cate: Category) -> String {
+        serde_json::to_string(&Verify {
+            id: String::from(id),
+            cate,
+            media_id,
+            account_id: 1,
+            url: String::new(),
+            body: None,
+            attempt: 0,
+            pages: None,
+            raw: String::new(),
+        })
+        .unwrap()
+    }
+
+    // Every handler stores its verifies the way `verify_rt` would leave them: done and recorded.
+    fn registry(groups: &[(u8, Category)], seen: &Seen) -> Registry {
+        let mut registry = Registry::default();
+        for &(media_id, cate) in groups {
+            let seen = Arc::clone(seen);
+            registry.register(
+                media_id,
+                cate,
+                move |verifies: Vec<Verify>| -> BoxFuture<()> {
+                    let seen = Arc::clone(&seen);
+                    Box::pin(async move {
+                        for verify in verifies {
+                            shutdown::done(&verify);
+                            seen.lock().unwrap().push((verify.media_id, verify.id));
+                        }
+                    })
+                },
+            );
+        }
+        registry
+    }
+
+    #[tokio::test]
+    async fn run_dispatches_what_the_channel_feeds() {
+        let _serial = SERIAL.lock().await;
+        let seen = Seen::default();
+        let registry = registry(&[(2, Category::AdgroupV3), (4, Category::Balance)], &seen);
+        let (tx, source) = channel::channel(8);
+        for (id, media_id, cate) in [
+            ("a", 2, Category::AdgroupV3),
+            ("b", 4, Category::Balance),
+            ("c", 2, Category::AdgroupV3),
+        ] {
+            tx.send(message(id, media_id, cate)).await.unwrap();
+        }
+        drop(tx);
+        run(source, &registry).await.unwrap();
+        let mut seen = seen.lock().unwrap().clone();
+        seen.sort();
+        assert_eq!(
+            seen,
+            vec![
+                (2, String::from("a")),
+                (2, String::from("c")),
+                (4, String::from("b"))
+            ]
+        );
+        assert!(shutdown::unfinished().is_empty());
+    }
+
+    #[tokio::test]
+    async fn a_panicking_group_fails_only_its_unfinished_verifies() {
+        let _serial = SERIAL.lock().await;
+        let seen = Seen::default();
+        let mut registry = registry(&[(4, Category::Balance)], &seen);
+        registry.register(
+            2,
+            Category::AdgroupV3,
+            |verifies: Vec<Verify>| -> BoxFuture<()> {
+                Box::pin(async move {
+                    shutdown::done(&verifies[0]);
+                    panic!("sync of {} verifies blew up", verifies.len());
+                })
+            },
+        );
+        let messages = vec![
+            message("a", 2, Category::AdgroupV3),
+            message("b", 2, Category::AdgroupV3),
+            message("c", 4, Category::Balance),
+        ];
+        let failed = join(start(messages, &registry).await).await;
+        let finished = ["a", "b"]
+            .into_iter()
+            .filter(|id| !failed.iter().any(|(verify, _)| verify.id == *id))
+            .collect::<Vec<_>>();
+        assert_eq!(failed.len(), 1);
+        assert_eq!(finished.len(), 1);
+        assert!(matches!(failed[0].1, Error::Dispatch(_)));
+        assert_eq!(*seen.lock().unwrap(), vec![(4, String::from("c"))]);
+        let unfinished = shutdown::unfinished();
+        assert_eq!(unfinished.len(), 1);
+        assert_eq!(unfinished[0].id, failed[0].0.id);
     }
-    Ok(())
 }
@@ -1,11 +1,16 @@
 #![recursion_limit = "1024"]
 use chrono::Local;
+use clap::Parser;
+use log::error;
 mod api;
 mod model;
 mod share;
+mod source;
 use std::io::Write;
 
 use crate::api::router;
+use crate::share::config::{self, Cli, Command};
+use crate::share::dlq;
 
 #[tokio::main]
 async fn main() {
@@ -23,5 +28,17 @@ async fn main() {
             )
         })
         .init();
-    router::route().await.unwrap();
+    let cli = Cli::parse();
+    if let Err(err) = config::init(&cli) {
+        error!("{}", err);
+        std::process::exit(1);
+    }
+    let rt = match cli.command {
+        Some(Command::Dlq { action }) => dlq::run(action),
+        None => router::route().await,
+    };
+    if let Err(err) = rt {
+        error!("{}", err);
+        std::process::exit(1);
+    }
 }
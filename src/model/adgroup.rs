@@ -154,3 +154,14 @@ impl AdGroup {
         }
     }
 }
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BaiduAdgroup {
+    pub adgroup_id: u64,
+    pub campaign_id: Option<u64>,
+    pub adgroup_name: Option<String>,
+    pub max_price: Option<f64>,
+    pub pause: Option<bool>,
+    pub status: Option<i32>,
+}
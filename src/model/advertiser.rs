@@ -19,3 +19,14 @@ pub struct TtAdvertiser {
     pub first_industry_name: Option<String>,
     pub second_industry_name: Option<String>,
 }
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BaiduAccount {
+    pub user_id: u64,
+    pub user_name: Option<String>,
+    pub balance: Option<f64>,
+    pub budget: Option<f64>,
+    pub budget_type: Option<i32>,
+    pub user_stat: Option<i32>,
+}
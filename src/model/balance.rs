@@ -33,3 +33,11 @@ pub struct GdtBalance {
     pub realtime_cost: Option<i64>,
     pub fund_status: Option<String>,
 }
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct KsBalance {
+    pub balance: Option<f64>,
+    pub recharge_balance: Option<f64>,
+    pub direct_rebate: Option<f64>,
+    pub contract_rebate: Option<f64>,
+}
@@ -3,9 +3,16 @@ use ::serde::{Deserialize, Serialize};
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct EmarTokenRt {
     pub code: i32,
+    pub message: Option<String>,
     pub access_token: Option<String>,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct OauthToken {
+    pub access_token: String,
+    pub refresh_token: String,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Pagination {
     pub page: u64,
@@ -43,3 +50,78 @@ pub struct TtApiCustomRes<T> {
     pub rows: Option<Vec<T>>,
     pub page_info: Option<Pagination>,
 }
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct KsPage<T> {
+    pub total_count: Option<u64>,
+    pub details: Option<Vec<T>>,
+}
+
+#[serde_with::skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BaiduReqHeader {
+    pub access_token: String,
+    pub user_name: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BaiduReq<T> {
+    pub header: BaiduReqHeader,
+    pub body: T,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BaiduFailure {
+    pub code: i32,
+    pub message: Option<String>,
+    pub position: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BaiduResHeader {
+    pub status: i32,
+    pub desc: Option<String>,
+    pub failures: Option<Vec<BaiduFailure>>,
+    pub quota: Option<i64>,
+    pub rquota: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BaiduResBody<T> {
+    pub data: Option<T>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BaiduRes<T> {
+    pub header: BaiduResHeader,
+    pub body: Option<BaiduResBody<T>>,
+}
+
+impl<T> From<BaiduRes<T>> for ApiRes<T> {
+    fn from(res: BaiduRes<T>) -> Self {
+        let failure = res
+            .header
+            .failures
+            .and_then(|failures| failures.into_iter().next());
+        let (code, message) = match (res.header.status, failure) {
+            (0, _) => (0, res.header.desc),
+            (_, Some(failure)) => (failure.code, failure.message),
+            (status, None) => (status, res.header.desc),
+        };
+        ApiRes {
+            code,
+            message,
+            message_cn: None,
+            data: res.body.and_then(|body| body.data),
+            request_id: None,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BaiduReportData<T> {
+    pub total_row_count: Option<u64>,
+    pub rows: Option<Vec<T>>,
+}
@@ -0,0 +1,23 @@
+use ::serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct KsCampaign {
+    pub campaign_id: u64,
+    pub campaign_name: Option<String>,
+    pub campaign_type: Option<i32>,
+    pub put_status: Option<i32>,
+    pub status: Option<i32>,
+    pub day_budget: Option<i64>,
+    pub create_time: Option<String>,
+    pub update_time: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BaiduCampaign {
+    pub campaign_id: u64,
+    pub campaign_name: Option<String>,
+    pub budget: Option<f64>,
+    pub pause: Option<bool>,
+    pub status: Option<i32>,
+}
@@ -0,0 +1,169 @@
+use ::serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[serde(try_from = "String", into = "&'static str")]
+pub enum Category {
+    AdgroupV3,
+    DynamicCreativeV3,
+    AdgroupDailyRequestPartV3,
+    AdvertiserReportingPartV3,
+    DynamicCreativeDailyRequestPartV3,
+    AdvertiserDailyReportingPartV3,
+    Advertiser,
+    Target,
+    Audience,
+    Balance,
+    Project,
+    Promotion,
+    Budget,
+    RtaInfo,
+    Account,
+    AdvertiserHourlyReport,
+    ProjectHourlyReport,
+    PromotionHourlyReport,
+    Campaign,
+    Unit,
+    Creative,
+    AccountHourlyReport,
+    UnitHourlyReport,
+    Adgroup,
+    Keyword,
+    AccountDailyReport,
+    KeywordDailyReport,
+}
+
+impl Category {
+    pub const ALL: [Category; 27] = [
+        Category::AdgroupV3,
+        Category::DynamicCreativeV3,
+        Category::AdgroupDailyRequestPartV3,
+        Category::AdvertiserReportingPartV3,
+        Category::DynamicCreativeDailyRequestPartV3,
+        Category::AdvertiserDailyReportingPartV3,
+        Category::Advertiser,
+        Category::Target,
+        Category::Audience,
+        Category::Balance,
+        Category::Project,
+        Category::Promotion,
+        Category::Budget,
+        Category::RtaInfo,
+        Category::Account,
+        Category::AdvertiserHourlyReport,
+        Category::ProjectHourlyReport,
+        Category::PromotionHourlyReport,
+        Category::Campaign,
+        Category::Unit,
+        Category::Creative,
+        Category::AccountHourlyReport,
+        Category::UnitHourlyReport,
+        Category::Adgroup,
+        Category::Keyword,
+        Category::AccountDailyReport,
+        Category::KeywordDailyReport,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Category::AdgroupV3 => "adgroup_v3",
+            Category::DynamicCreativeV3 => "dynamic_creative_v3",
+            Category::AdgroupDailyRequestPartV3 => "adgroup_daily_request_part_v3",
+            Category::AdvertiserReportingPartV3 => "advertiser_reporting_part_v3",
+            Category::DynamicCreativeDailyRequestPartV3 => "dynamic_creative_daily_request_part_v3",
+            Category::AdvertiserDailyReportingPartV3 => "advertiser_daily_reporting_part_v3",
+            Category::Advertiser => "advertiser",
+            Category::Target => "target",
+            Category::Audience => "audience",
+            Category::Balance => "balance",
+            Category::Project => "project",
+            Category::Promotion => "promotion",
+            Category::Budget => "budget",
+            Category::RtaInfo => "rta_info",
+            Category::Account => "account",
+            Category::AdvertiserHourlyReport => "advertiser_hourly_report",
+            Category::ProjectHourlyReport => "project_hourly_report",
+            Category::PromotionHourlyReport => "promotion_hourly_report",
+            Category::Campaign => "campaign",
+            Category::Unit => "unit",
+            Category::Creative => "creative",
+            Category::AccountHourlyReport => "account_hourly_report",
+            Category::UnitHourlyReport => "unit_hourly_report",
+            Category::Adgroup => "adgroup",
+            Category::Keyword => "keyword",
+            Category::AccountDailyReport => "account_daily_report",
+            Category::KeywordDailyReport => "keyword_daily_report",
+        }
+    }
+}
+
+impl fmt::Display for Category {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Category {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Category::ALL
+            .iter()
+            .copied()
+            .find(|cate| cate.as_str() == s)
+            .ok_or_else(|| format!("unknown category {}", s))
+    }
+}
+
+impl TryFrom<String> for Category {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<Category> for &'static str {
+    fn from(cate: Category) -> Self {
+        cate.as_str()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_category_round_trips_through_its_name() {
+        for cate in Category::ALL {
+            assert_eq!(cate.as_str().parse::<Category>(), Ok(cate));
+            assert_eq!(cate.to_string(), cate.as_str());
+        }
+    }
+
+    #[test]
+    fn names_are_unique() {
+        let mut names = Category::ALL.map(|cate| cate.as_str()).to_vec();
+        names.sort_unstable();
+        names.dedup();
+        assert_eq!(names.len(), Category::ALL.len());
+    }
+
+    #[test]
+    fn serde_uses_the_name() {
+        let json = serde_json::to_string(&Category::AdgroupDailyRequestPartV3).unwrap();
+        assert_eq!(json, "\"adgroup_daily_request_part_v3\"");
+        assert_eq!(
+            serde_json::from_str::<Category>(&json).unwrap(),
+            Category::AdgroupDailyRequestPartV3
+        );
+    }
+
+    #[test]
+    fn unknown_names_are_rejected() {
+        assert!("adgroup_v4".parse::<Category>().is_err());
+        assert!("AdgroupV3".parse::<Category>().is_err());
+        assert!(serde_json::from_str::<Category>("\"\"").is_err());
+    }
+}
@@ -42,3 +42,32 @@ impl DynamicCreative {
         if v.is_null() { None } else { Some(v) }
     }
 }
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct KsCreative {
+    pub creative_id: u64,
+    pub unit_id: Option<u64>,
+    pub campaign_id: Option<u64>,
+    pub creative_name: Option<String>,
+    pub put_status: Option<i32>,
+    pub status: Option<i32>,
+    pub creative_material_type: Option<i32>,
+    pub photo_id: Option<String>,
+    pub create_time: Option<String>,
+    pub update_time: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BaiduCreative {
+    pub creative_id: u64,
+    pub adgroup_id: Option<u64>,
+    pub campaign_id: Option<u64>,
+    pub title: Option<String>,
+    pub description1: Option<String>,
+    pub description2: Option<String>,
+    pub pc_destination_url: Option<String>,
+    pub mobile_destination_url: Option<String>,
+    pub pause: Option<bool>,
+    pub status: Option<i32>,
+}
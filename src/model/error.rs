@@ -1,3 +1,4 @@
+use crate::model::category::Category;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -18,10 +19,52 @@ pub enum Error {
     Json(#[from] serde_json::Error),
 
     #[error("API Error {code}: {message}")]
-    Api { code: i32, message: String },
+    Api {
+        code: i32,
+        message: String,
+        request_id: Option<String>,
+    },
 
-    #[error("{0}")]
-    Custom(String),
+    #[error("Failed To Get Token: {0}")]
+    Token(String),
+
+    #[error("Unsupported Category {cate} For Media {media_id}")]
+    Unsupported { media_id: u8, cate: Category },
+
+    #[error("Task Error: {0}")]
+    Join(#[from] tokio::task::JoinError),
+
+    #[error("Dispatch Error: {0}")]
+    Dispatch(String),
+
+    #[error("IO Error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Config Error: {0}")]
+    Config(String),
+}
+
+impl Error {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Error::Database(_) => "database",
+            Error::Redis(_) => "redis",
+            Error::RedisPool(_) => "redis_pool",
+            Error::Http(_) => "http",
+            Error::Json(_) => "json",
+            Error::Api { .. } => "api",
+            Error::Token(_) => "token",
+            Error::Unsupported { .. } => "unsupported",
+            Error::Join(_) => "task",
+            Error::Dispatch(_) => "dispatch",
+            Error::Io(_) => "io",
+            Error::Config(_) => "config",
+        }
+    }
+
+    pub fn is_retryable(&self) -> bool {
+        !matches!(self, Error::Unsupported { .. } | Error::Config(_))
+    }
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -0,0 +1,14 @@
+use ::serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BaiduKeyword {
+    pub keyword_id: u64,
+    pub adgroup_id: Option<u64>,
+    pub campaign_id: Option<u64>,
+    pub keyword: Option<String>,
+    pub price: Option<f64>,
+    pub match_type: Option<i32>,
+    pub pause: Option<bool>,
+    pub status: Option<i32>,
+}
@@ -1,12 +1,28 @@
+use crate::model::category::Category;
 use ::serde::{Deserialize, Serialize};
 use serde_json::Value;
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct Verify {
     pub id: String,
-    pub cate: String,
+    pub cate: Category,
     pub media_id: u8,
     pub account_id: u64,
     pub url: String,
     pub body: Option<Value>,
+    #[serde(default)]
+    pub attempt: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pages: Option<Vec<u64>>,
+    // The message as the source delivered it, so it can be acked or put back byte for byte.
+    #[serde(skip)]
+    pub raw: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct DeadLetter {
+    pub verify: Verify,
+    pub error_kind: String,
+    pub message: String,
+    pub failed_at: String,
 }
@@ -2,11 +2,16 @@ pub mod adgroup;
 pub mod advertiser;
 pub mod balance;
 pub mod base;
+pub mod campaign;
+pub mod category;
 pub mod creative;
 pub mod error;
+pub mod keyword;
 pub mod message;
 pub mod project;
 pub mod promotion;
 pub mod report;
+pub mod result;
 pub mod rta;
 pub mod target;
+pub mod unit;
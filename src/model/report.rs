@@ -1044,3 +1044,45 @@ pub struct TtFlat {
     pub luban_live_comment_cnt: Option<String>,
     pub live_component_click_count: Option<String>,
 }
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct KsReport {
+    pub stat_date: String,
+    pub stat_hour: Option<u8>,
+    pub campaign_id: Option<u64>,
+    pub campaign_name: Option<String>,
+    pub unit_id: Option<u64>,
+    pub unit_name: Option<String>,
+    pub charge: Option<f64>,
+    pub show: Option<i64>,
+    pub photo_click: Option<i64>,
+    pub aclick: Option<i64>,
+    pub bclick: Option<i64>,
+    pub share: Option<i64>,
+    pub comment: Option<i64>,
+    pub likes: Option<i64>,
+    pub follow: Option<i64>,
+    pub play_3s: Option<i64>,
+    pub play_5s: Option<i64>,
+    pub play_end: Option<i64>,
+    pub activation: Option<i64>,
+    pub conversion_num: Option<i64>,
+    pub event_pay: Option<i64>,
+    pub event_pay_purchase_amount: Option<f64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BaiduReport {
+    pub date: String,
+    pub campaign_id: Option<u64>,
+    pub adgroup_id: Option<u64>,
+    pub keyword_id: Option<u64>,
+    pub keyword: Option<String>,
+    pub impression: Option<i64>,
+    pub click: Option<i64>,
+    pub cost: Option<f64>,
+    pub ctr: Option<f64>,
+    pub cpc: Option<f64>,
+    pub cpm: Option<f64>,
+}
@@ -0,0 +1,259 @@
+use crate::model::error::Error;
+use crate::model::message::Verify;
+use ::serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Status {
+    Ok,
+    Partial,
+    Failed,
+    Mismatch,
+    NotComparable,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct PageStats {
+    pub pages_expected: u64,
+    pub pages_fetched: u64,
+    pub rows_written: u64,
+    pub failed_pages: Vec<u64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum DiffKind {
+    Missing,
+    Extra,
+    Changed,
+}
+
+// One difference between the api and the stored row with the same key. Missing rows exist only
+// in the api, extra rows only in the table, changed rows carry the field and both values.
+#[serde_with::skip_serializing_none]
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct DiffEntry {
+    pub kind: DiffKind,
+    pub key: String,
+    pub field: Option<String>,
+    pub api: Option<Value>,
+    pub db: Option<Value>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct Diff {
+    pub table: String,
+    pub api_rows: u64,
+    pub db_rows: u64,
+    pub missing: u64,
+    pub extra: u64,
+    pub changed: u64,
+    pub entries: Vec<DiffEntry>,
+}
+
+impl Diff {
+    pub fn is_empty(&self) -> bool {
+        self.missing == 0 && self.extra == 0 && self.changed == 0
+    }
+}
+
+#[serde_with::skip_serializing_none]
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct VerifyResult {
+    pub id: String,
+    pub status: Status,
+    pub media_id: u8,
+    pub cate: String,
+    pub account_id: u64,
+    pub error_kind: Option<String>,
+    pub code: Option<i32>,
+    pub message: Option<String>,
+    pub request_id: Option<String>,
+    pub attempts: u32,
+    pub retries: u32,
+    pub duration_ms: u64,
+    #[serde(flatten)]
+    pub pages: Option<PageStats>,
+    pub diff: Option<Diff>,
+    pub finished_at: String,
+}
+
+impl VerifyResult {
+    pub fn new(
+        verify: &Verify,
+        err: Option<&Error>,
+        attempts: u32,
+        duration_ms: u64,
+        pages: Option<PageStats>,
+    ) -> Self {
+        let (code, request_id) = match err {
+            Some(Error::Api {
+                code, request_id, ..
+            }) => (Some(*code), request_id.clone()),
+            _ => (None, None),
+        };
+        let status = match (err, &pages) {
+            (None, _) => Status::Ok,
+            (Some(_), Some(stats)) if stats.pages_fetched > 0 => Status::Partial,
+            (Some(_), _) => Status::Failed,
+        };
+        VerifyResult {
+            id: verify.id.clone(),
+            status,
+            media_id: verify.media_id,
+            cate: verify.cate.to_string(),
+            account_id: verify.account_id,
+            error_kind: err.map(|e| e.kind().to_string()),
+            code,
+            message: err.map(|e| e.to_string()),
+            request_id,
+            attempts,
+            retries: verify.attempt,
+            duration_ms,
+            pages,
+            diff: None,
+            finished_at: chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+        }
+    }
+
+    // The failure of a message that did not parse into a `Verify`, e.g. one with an unknown
+    // category. Whatever fields can still be read are kept, without an id there is nothing to key
+    // the result by.
+    pub fn rejected(msg: &Value, err: &Error) -> Option<Self> {
+        let id = match msg.get("id")? {
+            Value::String(id) => id.clone(),
+            Value::Number(id) => id.to_string(),
+            _ => return None,
+        };
+        let number = |field: &str| {
+            msg.get(field)
+                .and_then(|value| value.as_u64().or_else(|| value.as_str()?.parse().ok()))
+        };
+        Some(VerifyResult {
+            id,
+            status: Status::Failed,
+            media_id: number("media_id")
+                .and_then(|media_id| u8::try_from(media_id).ok())
+                .unwrap_or_default(),
+            cate: msg
+                .get("cate")
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+                .to_string(),
+            account_id: number("account_id").unwrap_or_default(),
+            error_kind: Some(err.kind().to_string()),
+            code: None,
+            message: Some(err.to_string()),
+            request_id: None,
+            attempts: 0,
+            retries: number("attempt")
+                .and_then(|attempt| u32::try_from(attempt).ok())
+                .unwrap_or_default(),
+            duration_ms: 0,
+            pages: None,
+            diff: None,
+            finished_at: chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+        })
+    }
+
+    // A dry run that fetched cleanly but disagrees with the table is reported as a mismatch, one
+    // of a category that can not be read back as not comparable rather than as a clean match.
+    pub fn with_diff(mut self, diff: Option<Diff>) -> Self {
+        match diff {
+            Some(diff) => {
+                if self.status == Status::Ok && !diff.is_empty() {
+                    self.status = Status::Mismatch;
+                }
+                self.diff = Some(diff);
+            }
+            None if self.status == Status::Ok => self.status = Status::NotComparable,
+            None => {}
+        }
+        self
+    }
+
+    pub fn fields(&self) -> Vec<(String, String)> {
+        match serde_json::to_value(self) {
+            Ok(Value::Object(map)) => map
+                .into_iter()
+                .map(|(k, v)| match v {
+                    Value::String(s) => (k, s),
+                    other => (k, other.to_string()),
+                })
+                .collect(),
+            _ => vec![],
+        }
+    }
+}
+
+// A period where the parent level of a report disagrees with the sum of its children.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Discrepancy {
+    pub check: String,
+    pub media_id: u8,
+    pub account_id: u64,
+    pub period: String,
+    pub parent: f64,
+    pub children: f64,
+    pub delta: f64,
+    pub checked_at: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn unknown_category() -> Error {
+        Error::Json(
+            serde_json::from_str::<crate::model::message::Verify>(
+                r#"{"id":"1","cate":"nope","media_id":2,"account_id":3,"url":""}"#,
+            )
+            .unwrap_err(),
+        )
+    }
+
+    #[test]
+    fn rejected_keeps_what_can_be_read() {
+        let msg =
+            json!({"id": "v1", "cate": "nope", "media_id": 2, "account_id": "42", "attempt": 1});
+        let result = VerifyResult::rejected(&msg, &unknown_category()).unwrap();
+        assert_eq!(result.id, "v1");
+        assert_eq!(result.status, Status::Failed);
+        assert_eq!(result.media_id, 2);
+        assert_eq!(result.cate, "nope");
+        assert_eq!(result.account_id, 42);
+        assert_eq!(result.retries, 1);
+        assert_eq!(result.error_kind.as_deref(), Some("json"));
+        assert!(result.message.unwrap().contains("unknown category nope"));
+    }
+
+    #[test]
+    fn rejected_takes_numeric_ids_and_tolerates_bad_fields() {
+        let msg = json!({"id": 7, "media_id": 300, "account_id": "x"});
+        let result = VerifyResult::rejected(&msg, &unknown_category()).unwrap();
+        assert_eq!(result.id, "7");
+        assert_eq!(result.media_id, 0);
+        assert_eq!(result.account_id, 0);
+    }
+
+    #[test]
+    fn rejected_needs_an_id() {
+        assert!(VerifyResult::rejected(&json!({"cate": "nope"}), &unknown_category()).is_none());
+        assert!(VerifyResult::rejected(&Value::Null, &unknown_category()).is_none());
+    }
+
+    #[test]
+    fn a_dry_run_without_a_diff_is_not_comparable() {
+        let verify = serde_json::from_str::<crate::model::message::Verify>(
+            r#"{"id":"1","cate":"account","media_id":4,"account_id":3,"url":""}"#,
+        )
+        .unwrap();
+        let result = VerifyResult::new(&verify, None, 1, 0, None);
+        assert_eq!(result.clone().with_diff(None).status, Status::NotComparable);
+        assert_eq!(result.with_diff(Some(Diff::default())).status, Status::Ok);
+        let failed = VerifyResult::new(&verify, Some(&unknown_category()), 1, 0, None);
+        assert_eq!(failed.with_diff(None).status, Status::Failed);
+    }
+}
@@ -0,0 +1,19 @@
+use ::serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct KsUnit {
+    pub unit_id: u64,
+    pub campaign_id: Option<u64>,
+    pub unit_name: Option<String>,
+    pub put_status: Option<i32>,
+    pub status: Option<i32>,
+    pub bid_type: Option<i32>,
+    pub bid: Option<i64>,
+    pub cpa_bid: Option<i64>,
+    pub ocpx_action_type: Option<i32>,
+    pub day_budget: Option<i64>,
+    pub begin_time: Option<String>,
+    pub end_time: Option<String>,
+    pub create_time: Option<String>,
+    pub update_time: Option<String>,
+}
@@ -0,0 +1,372 @@
+use crate::model::category::Category;
+use crate::model::error::Result;
+use crate::share::common::{redis, slot_prefix};
+use crate::share::config::config;
+use crate::share::shutdown;
+use crate::share::sink::Db;
+use log::{info, warn};
+use redis::AsyncCommands;
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BinaryHeap};
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, LazyLock, Mutex, MutexGuard};
+use std::time::Duration;
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+use tokio::time::interval;
+
+// A named counting semaphore whose waiters are served by priority, highest first and in arrival
+// order within a priority, so a busy low priority group can not queue ahead of a higher one.
+struct Budget {
+    limit: usize,
+    state: Mutex<State>,
+}
+
+struct State {
+    free: usize,
+    arrivals: u64,
+    waiters: BinaryHeap<Waiter>,
+}
+
+struct Waiter {
+    priority: i32,
+    arrival: Reverse<u64>,
+    wake: oneshot::Sender<()>,
+}
+
+impl PartialEq for Waiter {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == std::cmp::Ordering::Equal
+    }
+}
+
+impl Eq for Waiter {}
+
+impl PartialOrd for Waiter {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Waiter {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        (self.priority, self.arrival).cmp(&(other.priority, other.arrival))
+    }
+}
+
+// Held while the work runs, dropping it hands the slot to the next waiter.
+pub struct Permit {
+    budget: Arc<Budget>,
+}
+
+impl Drop for Permit {
+    fn drop(&mut self) {
+        self.budget.release();
+    }
+}
+
+// A queued `acquire`. A permit handed over after the future was dropped goes back to the budget.
+struct Queued {
+    budget: Arc<Budget>,
+    woken: Option<oneshot::Receiver<()>>,
+}
+
+impl Drop for Queued {
+    fn drop(&mut self) {
+        if let Some(mut woken) = self.woken.take() {
+            woken.close();
+            if woken.try_recv().is_ok() {
+                self.budget.release();
+            }
+        }
+    }
+}
+
+impl Budget {
+    fn new(limit: usize) -> Self {
+        Budget {
+            limit,
+            state: Mutex::new(State {
+                free: limit,
+                arrivals: 0,
+                waiters: BinaryHeap::new(),
+            }),
+        }
+    }
+
+    fn state(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    async fn acquire(self: &Arc<Self>, priority: i32) -> Permit {
+        let woken = {
+            let mut state = self.state();
+            if state.free > 0 && state.waiters.is_empty() {
+                state.free -= 1;
+                return Permit {
+                    budget: Arc::clone(self),
+                };
+            }
+            let (wake, woken) = oneshot::channel();
+            state.arrivals += 1;
+            let arrival = Reverse(state.arrivals);
+            state.waiters.push(Waiter {
+                priority,
+                arrival,
+                wake,
+            });
+            woken
+        };
+        let mut queued = Queued {
+            budget: Arc::clone(self),
+            woken: Some(woken),
+        };
+        if let Some(woken) = queued.woken.as_mut() {
+            woken.await.expect("Budget Closed");
+        }
+        queued.woken = None;
+        Permit {
+            budget: Arc::clone(self),
+        }
+    }
+
+    // Hands the slot to the highest waiter still listening, or frees it.
+    fn release(&self) {
+        let mut state = self.state();
+        while let Some(waiter) = state.waiters.pop() {
+            if waiter.wake.send(()).is_ok() {
+                return;
+            }
+        }
+        state.free += 1;
+    }
+
+    fn available(&self) -> usize {
+        self.state().free
+    }
+
+    fn waiting(&self) -> usize {
+        let state = self.state();
+        state
+            .waiters
+            .iter()
+            .filter(|waiter| !waiter.wake.is_closed())
+            .count()
+    }
+
+    fn in_flight(&self) -> usize {
+        self.limit - self.available()
+    }
+}
+
+// Keyed `global`, `pages`, `db:<pool>`, `media:<media_id>` and `category:<media_id>_<category>`.
+static BUDGETS: LazyLock<Mutex<BTreeMap<String, Arc<Budget>>>> =
+    LazyLock::new(|| Mutex::new(BTreeMap::new()));
+
+static QUEUE_DEPTH: AtomicU64 = AtomicU64::new(0);
+
+fn budget(name: String, limit: usize) -> Arc<Budget> {
+    let mut budgets = BUDGETS.lock().unwrap_or_else(|e| e.into_inner());
+    Arc::clone(
+        budgets
+            .entry(name)
+            .or_insert_with(|| Arc::new(Budget::new(limit))),
+    )
+}
+
+fn global() -> Arc<Budget> {
+    budget(String::from("global"), config().concurrency.global)
+}
+
+// Spawns a verify task once its category, media and the global budget have room. Waiting here
+// holds up the `*_sync` loop and with it the next fetch from the source. The order goes from the
+// narrowest budget to the widest so a busy category does not sit on global permits, and the group's
+// `[priority]` decides who gets a freed permit first.
+pub async fn spawn<F>(media_id: u8, cate: Category, task: F) -> JoinHandle<F::Output>
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    let concurrency = &config().concurrency;
+    let priority = config().priority(media_id, cate);
+    let mut permits = Vec::with_capacity(3);
+    for budget in [
+        budget(
+            format!("category:{}_{}", media_id, cate),
+            concurrency.per_category,
+        ),
+        budget(format!("media:{}", media_id), concurrency.per_media),
+        global(),
+    ] {
+        permits.push(budget.acquire(priority).await);
+    }
+    let handle = tokio::spawn(async move {
+        let _permits = permits;
+        task.await
+    });
+    shutdown::spawned(handle.abort_handle());
+    handle
+}
+
+// Pages draw from their own budget: the verify task that spawned them keeps its permits while
+// waiting, so sharing one pool could leave every permit with a verify waiting on its pages.
+pub async fn spawn_page<F>(media_id: u8, cate: Category, task: F) -> JoinHandle<F::Output>
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    let permit = budget(String::from("pages"), config().concurrency.pages)
+        .acquire(config().priority(media_id, cate))
+        .await;
+    let handle = tokio::spawn(async move {
+        let _permit = permit;
+        task.await
+    });
+    shutdown::spawned(handle.abort_handle());
+    handle
+}
+
+pub async fn db(db: Db) -> Permit {
+    let (name, pool) = match db {
+        Db::Tidb => ("db:tidb", &config().tidb),
+        Db::Core => ("db:core", &config().core),
+    };
+    budget(String::from(name), pool.pool_max).acquire(0).await
+}
+
+// How many messages to fetch next: waits until the global budget has room and never asks for more
+// than it could start right away. It queues below every group so verifies already fetched go first.
+pub async fn room(batch_size: usize) -> usize {
+    room_in(&global(), batch_size).await
+}
+
+async fn room_in(global: &Arc<Budget>, batch_size: usize) -> usize {
+    drop(global.acquire(i32::MIN).await);
+    batch_size.min(global.available()).max(1)
+}
+
+pub fn queue_depth(depth: u64) {
+    QUEUE_DEPTH.store(depth, Ordering::Relaxed);
+}
+
+fn snapshot() -> Vec<(String, u64)> {
+    let mut fields = vec![(
+        String::from("queue_depth"),
+        QUEUE_DEPTH.load(Ordering::Relaxed),
+    )];
+    let budgets = BUDGETS.lock().unwrap_or_else(|e| e.into_inner());
+    for (name, budget) in budgets.iter() {
+        fields.push((format!("{}:in_flight", name), budget.in_flight() as u64));
+        fields.push((format!("{}:waiting", name), budget.waiting() as u64));
+        fields.push((format!("{}:limit", name), budget.limit as u64));
+    }
+    fields
+}
+
+// Logs the queue depth and every budget, and mirrors them into `<result_key>:concurrency:<consumer>` so
+// `HGETALL` shows what an instance is working on.
+pub fn spawn_reporter() {
+    let every = Duration::from_secs(config().concurrency.report_secs);
+    let key = format!(
+        "{}:concurrency:{}",
+        slot_prefix(&config().queue.result_key),
+        config().source.consumer
+    );
+    tokio::spawn(async move {
+        let mut ticker = interval(every);
+        loop {
+            ticker.tick().await;
+            let fields = snapshot();
+            info!(
+                "Concurrency {}",
+                fields
+                    .iter()
+                    .filter(|(name, _)| !name.ends_with(":limit"))
+                    .map(|(name, value)| format!("{}={}", name, value))
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            );
+            let rt: Result<()> = async {
+                let mut conn = redis().await?;
+                let _: () = conn.hset_multiple(&key, &fields).await?;
+                let _: bool = conn.expire(&key, (every.as_secs() * 3) as i64).await?;
+                Ok(())
+            }
+            .await;
+            if let Err(err) = rt {
+                warn!("Publish Concurrency Failed Reason {}", err);
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::time::timeout;
+
+    #[tokio::test]
+    async fn room_caps_the_batch_at_the_free_permits() {
+        let global = Arc::new(Budget::new(4));
+        assert_eq!(room_in(&global, 10).await, 4);
+        assert_eq!(room_in(&global, 2).await, 2);
+        let _held = [
+            global.acquire(0).await,
+            global.acquire(0).await,
+            global.acquire(0).await,
+        ];
+        assert_eq!(room_in(&global, 10).await, 1);
+    }
+
+    #[tokio::test]
+    async fn room_waits_for_a_free_permit() {
+        let global = Arc::new(Budget::new(1));
+        let held = global.acquire(0).await;
+        assert!(
+            timeout(Duration::from_millis(20), room_in(&global, 10))
+                .await
+                .is_err()
+        );
+        drop(held);
+        assert_eq!(room_in(&global, 10).await, 1);
+    }
+
+    #[tokio::test]
+    async fn freed_permits_go_to_the_highest_priority_first() {
+        let budget = Arc::new(Budget::new(1));
+        let held = budget.acquire(0).await;
+        let order = Arc::new(Mutex::new(vec![]));
+        let mut handles = vec![];
+        for priority in [0, 5, 1, 5] {
+            let (budget, order) = (Arc::clone(&budget), Arc::clone(&order));
+            handles.push(tokio::spawn(async move {
+                let _permit = budget.acquire(priority).await;
+                order.lock().unwrap().push(priority);
+            }));
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        assert_eq!(budget.waiting(), 4);
+        drop(held);
+        for handle in handles {
+            handle.await.unwrap();
+        }
+        assert_eq!(*order.lock().unwrap(), vec![5, 5, 1, 0]);
+        assert_eq!(budget.available(), 1);
+    }
+
+    #[tokio::test]
+    async fn a_dropped_waiter_gives_its_turn_back() {
+        let budget = Arc::new(Budget::new(1));
+        let held = budget.acquire(0).await;
+        assert!(
+            timeout(Duration::from_millis(20), budget.acquire(5))
+                .await
+                .is_err()
+        );
+        assert_eq!(budget.waiting(), 0);
+        drop(held);
+        assert_eq!(budget.available(), 1);
+        assert_eq!(budget.in_flight(), 0);
+    }
+}
@@ -0,0 +1,947 @@
+use crate::model::category::Category;
+use crate::model::error::{Error, Result};
+use crate::share::common;
+use crate::share::dlq::DlqAction;
+use crate::share::limiter;
+use clap::{Parser, Subcommand, ValueEnum};
+use log::info;
+use mysql::Opts;
+use redis::IntoConnectionInfo;
+use serde::{Deserialize, Deserializer};
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+const DEFAULT_CONFIG_PATH: &str = "config.toml";
+
+static CONFIG: OnceLock<Config> = OnceLock::new();
+
+#[derive(Parser, Debug)]
+#[command(version, about)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    #[arg(long, env = "MAV_CONFIG")]
+    pub config: Option<PathBuf>,
+
+    #[arg(long, env = "MAV_TIDB_URL", hide_env_values = true)]
+    pub tidb_url: Option<String>,
+
+    #[arg(long, env = "MAV_TIDB_POOL_MIN")]
+    pub tidb_pool_min: Option<usize>,
+
+    #[arg(long, env = "MAV_TIDB_POOL_MAX")]
+    pub tidb_pool_max: Option<usize>,
+
+    #[arg(long, env = "MAV_CORE_URL", hide_env_values = true)]
+    pub core_url: Option<String>,
+
+    #[arg(long, env = "MAV_CORE_POOL_MIN")]
+    pub core_pool_min: Option<usize>,
+
+    #[arg(long, env = "MAV_CORE_POOL_MAX")]
+    pub core_pool_max: Option<usize>,
+
+    #[arg(
+        long,
+        env = "MAV_REDIS_NODES",
+        value_delimiter = ',',
+        hide_env_values = true
+    )]
+    pub redis_nodes: Option<Vec<String>>,
+
+    #[arg(long, env = "MAV_REDIS_POOL_SIZE")]
+    pub redis_pool_size: Option<u32>,
+
+    #[arg(long, env = "MAV_SOURCE")]
+    pub source: Option<SourceKind>,
+
+    #[arg(long, env = "MAV_SOURCE_PATH")]
+    pub source_path: Option<String>,
+
+    #[arg(long, env = "MAV_VERIFY_KEY")]
+    pub verify_key: Option<String>,
+
+    #[arg(long, env = "MAV_RESULT_KEY")]
+    pub result_key: Option<String>,
+
+    #[arg(long, env = "MAV_BATCH_SIZE")]
+    pub batch_size: Option<usize>,
+
+    #[arg(long, env = "MAV_TOKEN_URL")]
+    pub token_url: Option<String>,
+
+    #[arg(long, env = "MAV_TOKEN_PROVIDER")]
+    pub token_provider: Option<TokenProviderKind>,
+
+    #[arg(long, env = "MAV_TOKEN_PATH")]
+    pub token_path: Option<PathBuf>,
+
+    #[arg(long, env = "MAV_SINK")]
+    pub sink: Option<SinkKind>,
+
+    #[arg(long, env = "MAV_SINK_DIR")]
+    pub sink_dir: Option<PathBuf>,
+
+    #[arg(long, env = "MAV_DRY_RUN")]
+    pub dry_run: bool,
+
+    #[arg(
+        long = "rate-limit",
+        env = "MAV_RATE_LIMITS",
+        value_delimiter = ',',
+        value_parser = parse_rate_limit
+    )]
+    pub rate_limits: Vec<(String, usize)>,
+
+    #[arg(
+        long = "account-rate-limit",
+        env = "MAV_ACCOUNT_RATE_LIMITS",
+        value_delimiter = ',',
+        value_parser = parse_rate_limit
+    )]
+    pub account_rate_limits: Vec<(String, usize)>,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    Dlq {
+        #[command(subcommand)]
+        action: DlqAction,
+    },
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub tidb: DbConfig,
+    #[serde(deserialize_with = "DbConfig::core_section")]
+    pub core: DbConfig,
+    pub redis: RedisConfig,
+    pub queue: QueueConfig,
+    pub source: SourceConfig,
+    pub retry: RetryConfig,
+    pub token: TokenConfig,
+    pub limiter: LimiterConfig,
+    pub sink: SinkConfig,
+    pub reconcile: ReconcileConfig,
+    pub concurrency: ConcurrencyConfig,
+    pub priority: HashMap<String, i32>,
+    pub shutdown: ShutdownConfig,
+    pub rate_limit: HashMap<String, usize>,
+    pub account_rate_limit: HashMap<String, usize>,
+}
+
+#[derive(Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DbConfig {
+    pub url: String,
+    pub pool_min: usize,
+    pub pool_max: usize,
+}
+
+#[derive(Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RedisConfig {
+    pub nodes: Vec<String>,
+    pub read_from_replicas: bool,
+    pub min_idle: u32,
+    pub pool_size: u32,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(default, deny_unknown_fields)]
+pub struct QueueConfig {
+    pub verify_key: String,
+    pub result_key: String,
+    pub result_ttl_secs: i64,
+    pub batch_size: usize,
+    pub idle_ms: u64,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, ValueEnum)]
+#[serde(rename_all = "snake_case")]
+#[value(rename_all = "snake_case")]
+pub enum SourceKind {
+    RedisList,
+    RedisReliable,
+    RedisStream,
+    File,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(default, deny_unknown_fields)]
+pub struct SourceConfig {
+    pub kind: SourceKind,
+    pub path: String,
+    pub stream_key: String,
+    pub group: String,
+    pub consumer: String,
+    pub field: String,
+    pub block_ms: usize,
+    pub visibility_timeout_secs: u64,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(default, deny_unknown_fields)]
+pub struct RetryConfig {
+    pub max_attempts: u32,
+    pub base_delay_secs: u64,
+    pub max_delay_secs: u64,
+    pub poll_ms: u64,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, ValueEnum)]
+#[serde(rename_all = "snake_case")]
+#[value(rename_all = "snake_case")]
+pub enum TokenProviderKind {
+    Http,
+    File,
+    Oauth,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(default, deny_unknown_fields)]
+pub struct TokenConfig {
+    pub provider: TokenProviderKind,
+    pub url: String,
+    pub ttl_secs: u64,
+    pub path: PathBuf,
+    pub oauth: OauthConfig,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct OauthConfig {
+    pub gdt_client_id: u64,
+    pub gdt_client_secret: String,
+    pub tt_app_id: u64,
+    pub tt_secret: String,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, ValueEnum)]
+#[serde(rename_all = "snake_case")]
+#[value(rename_all = "snake_case")]
+pub enum SinkKind {
+    Mysql,
+    Jsonl,
+    Csv,
+    // Rows a production run could never read back, so only tests can pick it.
+    #[cfg(test)]
+    Memory,
+    Diff,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(default, deny_unknown_fields)]
+pub struct SinkConfig {
+    pub kind: SinkKind,
+    pub dir: PathBuf,
+    pub float_tolerance: f64,
+    pub max_placeholders: usize,
+    pub max_packet_bytes: usize,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(default, deny_unknown_fields)]
+pub struct ReconcileConfig {
+    pub enabled: bool,
+    pub days: u32,
+    pub tolerance: f64,
+    pub ratio: f64,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(default, deny_unknown_fields)]
+pub struct ConcurrencyConfig {
+    pub global: usize,
+    pub per_media: usize,
+    pub per_category: usize,
+    pub pages: usize,
+    pub report_secs: u64,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(default, deny_unknown_fields)]
+pub struct ShutdownConfig {
+    pub drain_secs: u64,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(default, deny_unknown_fields)]
+pub struct LimiterConfig {
+    pub distributed: bool,
+    pub key_prefix: String,
+    pub default_qps: usize,
+}
+
+impl DbConfig {
+    fn tidb() -> Self {
+        DbConfig {
+            url: String::new(),
+            pool_min: 5,
+            pool_max: 10,
+        }
+    }
+
+    fn core() -> Self {
+        DbConfig {
+            url: String::new(),
+            pool_min: 2,
+            pool_max: 4,
+        }
+    }
+
+    // `[core]` falls back to the core pool sizes rather than the tidb ones of `Default`.
+    fn core_section<'de, D: Deserializer<'de>>(de: D) -> std::result::Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(deny_unknown_fields)]
+        struct Section {
+            url: Option<String>,
+            pool_min: Option<usize>,
+            pool_max: Option<usize>,
+        }
+        let section = Section::deserialize(de)?;
+        let core = DbConfig::core();
+        Ok(DbConfig {
+            url: section.url.unwrap_or(core.url),
+            pool_min: section.pool_min.unwrap_or(core.pool_min),
+            pool_max: section.pool_max.unwrap_or(core.pool_max),
+        })
+    }
+
+    fn validate(&self, name: &str) -> Result<()> {
+        if self.url.is_empty() {
+            return Err(Error::Config(format!("{}.url is required", name)));
+        }
+        if Opts::from_url(&self.url).is_err() {
+            return Err(Error::Config(format!(
+                "{}.url is not a valid mysql url",
+                name
+            )));
+        }
+        if self.pool_max == 0 || self.pool_min > self.pool_max {
+            return Err(Error::Config(format!(
+                "{}.pool_min must not exceed {}.pool_max and pool_max must be positive",
+                name, name
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl Default for DbConfig {
+    fn default() -> Self {
+        DbConfig::tidb()
+    }
+}
+
+impl fmt::Debug for DbConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DbConfig")
+            .field("url", &redact(&self.url))
+            .field("pool_min", &self.pool_min)
+            .field("pool_max", &self.pool_max)
+            .finish()
+    }
+}
+
+impl Default for RedisConfig {
+    fn default() -> Self {
+        RedisConfig {
+            nodes: vec![],
+            read_from_replicas: true,
+            min_idle: 3,
+            pool_size: 10,
+        }
+    }
+}
+
+impl fmt::Debug for RedisConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RedisConfig")
+            .field(
+                "nodes",
+                &self.nodes.iter().map(|n| redact(n)).collect::<Vec<_>>(),
+            )
+            .field("read_from_replicas", &self.read_from_replicas)
+            .field("min_idle", &self.min_idle)
+            .field("pool_size", &self.pool_size)
+            .finish()
+    }
+}
+
+impl fmt::Debug for OauthConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OauthConfig")
+            .field("gdt_client_id", &self.gdt_client_id)
+            .field("gdt_client_secret", &"***")
+            .field("tt_app_id", &self.tt_app_id)
+            .field("tt_secret", &"***")
+            .finish()
+    }
+}
+
+impl Default for QueueConfig {
+    fn default() -> Self {
+        QueueConfig {
+            verify_key: String::from("rule_message_thread_rule_verify"),
+            result_key: String::from("rule_message_thread_rule_verify_rt"),
+            result_ttl_secs: 7 * 24 * 3600,
+            batch_size: 1000,
+            idle_ms: 1000,
+        }
+    }
+}
+
+impl Default for SourceConfig {
+    fn default() -> Self {
+        SourceConfig {
+            kind: SourceKind::RedisList,
+            path: String::from("-"),
+            stream_key: String::from("rule_message_thread_rule_verify_stream"),
+            group: String::from("marketing_api_verify"),
+            consumer: default_consumer(),
+            field: String::from("payload"),
+            block_ms: 1000,
+            visibility_timeout_secs: 300,
+        }
+    }
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        RetryConfig {
+            max_attempts: 5,
+            base_delay_secs: 30,
+            max_delay_secs: 3600,
+            poll_ms: 1000,
+        }
+    }
+}
+
+impl Default for TokenConfig {
+    fn default() -> Self {
+        TokenConfig {
+            provider: TokenProviderKind::Http,
+            url: String::from("http://marketingtoken.yixintui.com/marketing/api/token"),
+            ttl_secs: 600,
+            path: PathBuf::from("tokens.toml"),
+            oauth: OauthConfig::default(),
+        }
+    }
+}
+
+impl Default for SinkConfig {
+    fn default() -> Self {
+        SinkConfig {
+            kind: SinkKind::Mysql,
+            dir: PathBuf::from("sink"),
+            float_tolerance: 0.001,
+            max_placeholders: 65535,
+            max_packet_bytes: 4 << 20,
+        }
+    }
+}
+
+impl Default for ReconcileConfig {
+    fn default() -> Self {
+        ReconcileConfig {
+            enabled: true,
+            days: 3,
+            tolerance: 0.01,
+            ratio: 0.001,
+        }
+    }
+}
+
+impl Default for ConcurrencyConfig {
+    fn default() -> Self {
+        ConcurrencyConfig {
+            global: 256,
+            per_media: 128,
+            per_category: 64,
+            pages: 256,
+            report_secs: 30,
+        }
+    }
+}
+
+impl Default for ShutdownConfig {
+    fn default() -> Self {
+        ShutdownConfig { drain_secs: 30 }
+    }
+}
+
+impl Default for LimiterConfig {
+    fn default() -> Self {
+        LimiterConfig {
+            distributed: true,
+            key_prefix: String::from("marketing_api_verify:limiter"),
+            default_qps: 10,
+        }
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            tidb: DbConfig::tidb(),
+            core: DbConfig::core(),
+            redis: RedisConfig::default(),
+            queue: QueueConfig::default(),
+            source: SourceConfig::default(),
+            retry: RetryConfig::default(),
+            token: TokenConfig::default(),
+            limiter: LimiterConfig::default(),
+            sink: SinkConfig::default(),
+            reconcile: ReconcileConfig::default(),
+            concurrency: ConcurrencyConfig::default(),
+            priority: HashMap::new(),
+            shutdown: ShutdownConfig::default(),
+            rate_limit: HashMap::new(),
+            account_rate_limit: HashMap::new(),
+        }
+    }
+}
+
+impl Config {
+    pub fn load(cli: &Cli) -> Result<Config> {
+        let mut config = match &cli.config {
+            Some(path) => Config::from_file(path)?,
+            None if Path::new(DEFAULT_CONFIG_PATH).exists() => {
+                Config::from_file(Path::new(DEFAULT_CONFIG_PATH))?
+            }
+            None => Config::default(),
+        };
+        config.apply(cli);
+        config.validate()?;
+        Ok(config)
+    }
+
+    fn from_file(path: &Path) -> Result<Config> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| Error::Config(format!("{}: {}", path.display(), e)))?;
+        toml::from_str(&content)
+            .map_err(|e| Error::Config(format!("{}: {}", path.display(), e.message())))
+    }
+
+    fn apply(&mut self, cli: &Cli) {
+        if let Some(url) = &cli.tidb_url {
+            self.tidb.url = url.clone();
+        }
+        if let Some(min) = cli.tidb_pool_min {
+            self.tidb.pool_min = min;
+        }
+        if let Some(max) = cli.tidb_pool_max {
+            self.tidb.pool_max = max;
+        }
+        if let Some(url) = &cli.core_url {
+            self.core.url = url.clone();
+        }
+        if let Some(min) = cli.core_pool_min {
+            self.core.pool_min = min;
+        }
+        if let Some(max) = cli.core_pool_max {
+            self.core.pool_max = max;
+        }
+        if let Some(nodes) = &cli.redis_nodes {
+            self.redis.nodes = nodes.clone();
+        }
+        if let Some(size) = cli.redis_pool_size {
+            self.redis.pool_size = size;
+        }
+        if let Some(kind) = cli.source {
+            self.source.kind = kind;
+        }
+        if let Some(path) = &cli.source_path {
+            self.source.path = path.clone();
+        }
+        if let Some(key) = &cli.verify_key {
+            self.queue.verify_key = key.clone();
+        }
+        if let Some(key) = &cli.result_key {
+            self.queue.result_key = key.clone();
+        }
+        if let Some(size) = cli.batch_size {
+            self.queue.batch_size = size;
+        }
+        if let Some(url) = &cli.token_url {
+            self.token.url = url.clone();
+        }
+        if let Some(provider) = cli.token_provider {
+            self.token.provider = provider;
+        }
+        if let Some(path) = &cli.token_path {
+            self.token.path = path.clone();
+        }
+        if let Some(kind) = cli.sink {
+            self.sink.kind = kind;
+        }
+        if let Some(dir) = &cli.sink_dir {
+            self.sink.dir = dir.clone();
+        }
+        if cli.dry_run {
+            self.sink.kind = SinkKind::Diff;
+        }
+        for (key, freq) in &cli.rate_limits {
+            self.rate_limit.insert(key.clone(), *freq);
+        }
+        for (key, freq) in &cli.account_rate_limits {
+            self.account_rate_limit.insert(key.clone(), *freq);
+        }
+    }
+
+    fn validate(&self) -> Result<()> {
+        self.tidb.validate("tidb")?;
+        self.core.validate("core")?;
+        if self.redis.nodes.is_empty() {
+            return Err(Error::Config(String::from("redis.nodes is required")));
+        }
+        for (idx, node) in self.redis.nodes.iter().enumerate() {
+            if node.as_str().into_connection_info().is_err() {
+                return Err(Error::Config(format!(
+                    "redis.nodes[{}] is not a valid redis url",
+                    idx
+                )));
+            }
+        }
+        if self.redis.pool_size == 0 || self.redis.min_idle > self.redis.pool_size {
+            return Err(Error::Config(String::from(
+                "redis.min_idle must not exceed redis.pool_size and pool_size must be positive",
+            )));
+        }
+        if self.queue.verify_key.is_empty() || self.queue.result_key.is_empty() {
+            return Err(Error::Config(String::from(
+                "queue.verify_key and queue.result_key must not be empty",
+            )));
+        }
+        for (name, key) in [
+            ("queue.verify_key", &self.queue.verify_key),
+            ("queue.result_key", &self.queue.result_key),
+            ("source.stream_key", &self.source.stream_key),
+        ] {
+            if let Err(reason) = common::check_slot_key(key) {
+                return Err(Error::Config(format!("{} {}", name, reason)));
+            }
+        }
+        if self.queue.batch_size == 0 || self.queue.result_ttl_secs <= 0 {
+            return Err(Error::Config(String::from(
+                "queue.batch_size and queue.result_ttl_secs must be positive",
+            )));
+        }
+        if self.limiter.default_qps == 0 {
+            return Err(Error::Config(String::from(
+                "limiter.default_qps must be positive",
+            )));
+        }
+        if self.limiter.distributed && self.limiter.key_prefix.is_empty() {
+            return Err(Error::Config(String::from(
+                "limiter.key_prefix is required when limiter.distributed is enabled",
+            )));
+        }
+        if self.sink.float_tolerance.is_nan() || self.sink.float_tolerance < 0.0 {
+            return Err(Error::Config(String::from(
+                "sink.float_tolerance must not be negative",
+            )));
+        }
+        if self.sink.max_placeholders == 0 || self.sink.max_placeholders > 65535 {
+            return Err(Error::Config(String::from(
+                "sink.max_placeholders must be between 1 and 65535",
+            )));
+        }
+        if self.sink.max_packet_bytes == 0 {
+            return Err(Error::Config(String::from(
+                "sink.max_packet_bytes must be positive",
+            )));
+        }
+        if self.reconcile.enabled
+            && (self.reconcile.days == 0
+                || self.reconcile.tolerance.is_nan()
+                || self.reconcile.tolerance < 0.0
+                || self.reconcile.ratio.is_nan()
+                || self.reconcile.ratio < 0.0)
+        {
+            return Err(Error::Config(String::from(
+                "reconcile.days must be positive and reconcile.tolerance and reconcile.ratio must not be negative",
+            )));
+        }
+        let concurrency = &self.concurrency;
+        if concurrency.global == 0
+            || concurrency.per_media == 0
+            || concurrency.per_category == 0
+            || concurrency.pages == 0
+            || concurrency.report_secs == 0
+        {
+            return Err(Error::Config(String::from(
+                "concurrency.global, per_media, per_category, pages and report_secs must be positive",
+            )));
+        }
+        match self.source.kind {
+            SourceKind::File if self.source.path.is_empty() => {
+                return Err(Error::Config(String::from(
+                    "source.path is required for the file source",
+                )));
+            }
+            SourceKind::RedisStream
+                if self.source.stream_key.is_empty()
+                    || self.source.group.is_empty()
+                    || self.source.consumer.is_empty()
+                    || self.source.field.is_empty()
+                    || self.source.visibility_timeout_secs == 0 =>
+            {
+                return Err(Error::Config(String::from(
+                    "source.stream_key, source.group, source.consumer, source.field and a positive source.visibility_timeout_secs are required for the redis_stream source",
+                )));
+            }
+            SourceKind::RedisReliable
+                if self.source.consumer.is_empty() || self.source.visibility_timeout_secs < 3 =>
+            {
+                return Err(Error::Config(String::from(
+                    "source.consumer is required and source.visibility_timeout_secs must be at least 3 for the redis_reliable source",
+                )));
+            }
+            _ => {}
+        }
+        if self.retry.max_attempts == 0
+            || self.retry.base_delay_secs == 0
+            || self.retry.max_delay_secs < self.retry.base_delay_secs
+            || self.retry.poll_ms == 0
+        {
+            return Err(Error::Config(String::from(
+                "retry.max_attempts, retry.base_delay_secs and retry.poll_ms must be positive and retry.max_delay_secs must not be below retry.base_delay_secs",
+            )));
+        }
+        match self.token.provider {
+            TokenProviderKind::Http if reqwest::Url::parse(&self.token.url).is_err() => {
+                return Err(Error::Config(String::from("token.url is not a valid url")));
+            }
+            TokenProviderKind::File | TokenProviderKind::Oauth if !self.token.path.is_file() => {
+                return Err(Error::Config(format!(
+                    "token.path {} is not a readable file",
+                    self.token.path.display()
+                )));
+            }
+            _ => {}
+        }
+        for (table, limits) in [
+            ("rate_limit", &self.rate_limit),
+            ("account_rate_limit", &self.account_rate_limit),
+        ] {
+            for key in limits.keys() {
+                if let Err(reason) = limiter::check_key(key) {
+                    return Err(Error::Config(format!("{}.\"{}\" {}", table, key, reason)));
+                }
+            }
+            if let Some((key, _)) = limits.iter().find(|(_, freq)| **freq == 0) {
+                return Err(Error::Config(format!(
+                    "{}.\"{}\" must be positive",
+                    table, key
+                )));
+            }
+        }
+        if let Some(key) = self.priority.keys().find(|key| {
+            key.split_once(':').is_none_or(|(media, cate)| {
+                media.parse::<u8>().is_err() || cate.parse::<Category>().is_err()
+            })
+        }) {
+            return Err(Error::Config(format!(
+                "priority.\"{}\" must be media_id:category",
+                key
+            )));
+        }
+        Ok(())
+    }
+
+    // Groups without an entry run at priority 0.
+    pub fn priority(&self, media_id: u8, cate: Category) -> i32 {
+        self.priority
+            .get(&format!("{}:{}", media_id, cate))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    // Returns the qps for an endpoint and whether it applies to each account separately.
+    pub fn quota(&self, media_id: u8, endpoint: &str) -> Option<(usize, bool)> {
+        let key = format!("{}:{}", media_id, endpoint);
+        match self.account_rate_limit.get(&key) {
+            Some(freq) => Some((*freq, true)),
+            None => self.rate_limit.get(&key).map(|freq| (*freq, false)),
+        }
+    }
+}
+
+pub fn init(cli: &Cli) -> Result<()> {
+    let config = Config::load(cli)?;
+    info!("Config Loaded: {:?}", config);
+    CONFIG
+        .set(config)
+        .map_err(|_| Error::Config(String::from("config already initialized")))
+}
+
+#[cfg(not(test))]
+pub fn config() -> &'static Config {
+    CONFIG.get().expect("Config Not Initialized")
+}
+
+// Unit tests never go through `init`, code reaching for the config runs on the defaults.
+#[cfg(test)]
+pub fn config() -> &'static Config {
+    CONFIG.get_or_init(Config::default)
+}
+
+// Containers all run as pid 1, so the host name and a random suffix keep worker ids apart.
+fn default_consumer() -> String {
+    let host = std::env::var("HOSTNAME")
+        .ok()
+        .or_else(|| std::fs::read_to_string("/etc/hostname").ok())
+        .map(|host| host.trim().to_string())
+        .filter(|host| !host.is_empty())
+        .unwrap_or_else(|| String::from("localhost"));
+    let suffix = std::iter::repeat_with(fastrand::alphanumeric)
+        .take(6)
+        .collect::<String>();
+    format!("verify-{}-{}-{}", host, std::process::id(), suffix)
+}
+
+fn parse_rate_limit(raw: &str) -> std::result::Result<(String, usize), String> {
+    let (key, freq) = raw
+        .split_once('=')
+        .ok_or_else(|| format!("expected <media>:<endpoint>=<qps>, got {}", raw))?;
+    let freq = freq
+        .parse::<usize>()
+        .map_err(|e| format!("invalid qps for {}: {}", key, e))?;
+    Ok((key.to_string(), freq))
+}
+
+fn redact(url: &str) -> String {
+    match reqwest::Url::parse(url) {
+        Ok(mut parsed) if parsed.password().is_some() => {
+            let _ = parsed.set_password(Some("***"));
+            parsed.to_string()
+        }
+        Ok(parsed) => parsed.to_string(),
+        Err(_) => String::from("<invalid>"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example() -> Config {
+        toml::from_str(include_str!("../../config.example.toml")).unwrap()
+    }
+
+    fn cli(args: &[&str]) -> Cli {
+        Cli::try_parse_from(std::iter::once("marketing_api_verify").chain(args.iter().copied()))
+            .unwrap()
+    }
+
+    fn rejected(config: &Config) -> String {
+        match config.validate() {
+            Err(Error::Config(reason)) => reason,
+            other => panic!("expected a config error, got {:?}", other.err()),
+        }
+    }
+
+    #[test]
+    fn example_is_valid() {
+        example().validate().unwrap();
+    }
+
+    #[test]
+    fn cli_overrides_file() {
+        let mut config = example();
+        config.apply(&cli(&[
+            "--tidb-pool-max",
+            "20",
+            "--batch-size",
+            "7",
+            "--rate-limit",
+            "2:adgroups/get=3",
+            "--dry-run",
+        ]));
+        config.validate().unwrap();
+        assert_eq!(config.tidb.pool_max, 20);
+        assert_eq!(config.tidb.pool_min, 5);
+        assert_eq!(config.queue.batch_size, 7);
+        assert_eq!(config.sink.kind, SinkKind::Diff);
+        assert_eq!(config.quota(2, "adgroups/get"), Some((3, false)));
+    }
+
+    #[test]
+    fn account_limit_wins_over_endpoint_limit() {
+        let mut config = example();
+        config.apply(&cli(&["--account-rate-limit", "2:adgroups/get=2"]));
+        assert_eq!(config.quota(2, "adgroups/get"), Some((2, true)));
+        assert_eq!(config.quota(2, "funds/get"), Some((5, false)));
+        assert_eq!(config.quota(2, "advertiser/get"), None);
+    }
+
+    #[test]
+    fn db_sections_fall_back_to_their_pool_defaults() {
+        let config = toml::from_str::<Config>(
+            "[tidb]\nurl = \"mysql://root@tidb:4000/ad\"\n[core]\nurl = \"mysql://root@core:3306/ad\"\n",
+        )
+        .unwrap();
+        assert_eq!((config.tidb.pool_min, config.tidb.pool_max), (5, 10));
+        assert_eq!((config.core.pool_min, config.core.pool_max), (2, 4));
+        assert_eq!(config.core.url, "mysql://root@core:3306/ad");
+        assert!(toml::from_str::<Config>("[core]\npool = 5\n").is_err());
+    }
+
+    #[test]
+    fn keys_without_a_usable_hash_tag_are_rejected() {
+        let mut config = example();
+        config.queue.result_key = String::from("mav:{}:results");
+        assert!(rejected(&config).starts_with("queue.result_key has braces"));
+    }
+
+    #[test]
+    fn unknown_fields_are_rejected() {
+        assert!(toml::from_str::<Config>("[limiter]\nqps = 5\n").is_err());
+    }
+
+    #[test]
+    fn category_key_names_the_endpoint() {
+        let mut config = example();
+        config.rate_limit.insert(String::from("2:adgroup_v3"), 5);
+        assert_eq!(
+            rejected(&config),
+            "rate_limit.\"2:adgroup_v3\" names a category, limits are keyed by endpoint: use \"2:adgroups/get\""
+        );
+    }
+
+    #[test]
+    fn unknown_endpoint_is_rejected() {
+        let mut config = example();
+        config
+            .account_rate_limit
+            .insert(String::from("5:unit/get"), 5);
+        assert!(
+            rejected(&config)
+                .starts_with("account_rate_limit.\"5:unit/get\" is not an endpoint of media 5")
+        );
+
+        let mut config = example();
+        config.rate_limit.insert(String::from("adgroups/get"), 5);
+        assert_eq!(
+            rejected(&config),
+            "rate_limit.\"adgroups/get\" must be media_id:endpoint"
+        );
+    }
+
+    #[test]
+    fn zero_qps_is_rejected() {
+        let mut config = example();
+        config.limiter.default_qps = 0;
+        assert_eq!(rejected(&config), "limiter.default_qps must be positive");
+
+        let mut config = example();
+        config.rate_limit.insert(String::from("4:fund/get"), 0);
+        assert!(rejected(&config).contains("\"4:fund/get\""));
+    }
+}
@@ -0,0 +1,667 @@
+use crate::model::category::Category;
+use crate::model::error::Result;
+use crate::model::message::Verify;
+use crate::model::result::{Diff, DiffEntry, DiffKind};
+use crate::share::common::{CORE_POOL, TIDB_POOL};
+use crate::share::config::config;
+use crate::share::sink::{self, Batch, Db, Sink, Table};
+use crate::share::upsert;
+use log::{info, warn};
+use mysql::prelude::Queryable;
+use mysql::{Params, Row};
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::sync::{LazyLock, Mutex};
+
+// Only the first entries are kept in the result, the counts cover every difference.
+const MAX_ENTRIES: usize = 100;
+
+// How the rows of a category are read back: the column holding the account and the columns that
+// identify a row. Report tables are compared for the dates the api returned only, snapshot tables
+// whose upsert stamps `today` with CURDATE() for the current day only.
+struct DiffSpec {
+    media_id: u8,
+    cate: Category,
+    account: &'static str,
+    keys: &'static [&'static str],
+    today: Option<&'static str>,
+}
+
+const DIFF_SPECS: &[DiffSpec] = &[
+    // GDT
+    DiffSpec {
+        media_id: 2,
+        cate: Category::AdgroupV3,
+        account: "account_id",
+        keys: &["adgroup_id"],
+        today: None,
+    },
+    DiffSpec {
+        media_id: 2,
+        cate: Category::DynamicCreativeV3,
+        account: "account_id",
+        keys: &["dynamic_creative_id"],
+        today: None,
+    },
+    DiffSpec {
+        media_id: 2,
+        cate: Category::AdgroupDailyRequestPartV3,
+        account: "account_id",
+        keys: &["date", "adgroup_id"],
+        today: None,
+    },
+    DiffSpec {
+        media_id: 2,
+        cate: Category::AdvertiserReportingPartV3,
+        account: "account_id",
+        keys: &["date", "hour"],
+        today: None,
+    },
+    DiffSpec {
+        media_id: 2,
+        cate: Category::DynamicCreativeDailyRequestPartV3,
+        account: "account_id",
+        keys: &["date", "dynamic_creative_id"],
+        today: None,
+    },
+    DiffSpec {
+        media_id: 2,
+        cate: Category::AdvertiserDailyReportingPartV3,
+        account: "account_id",
+        keys: &["date"],
+        today: None,
+    },
+    DiffSpec {
+        media_id: 2,
+        cate: Category::Target,
+        account: "account_id",
+        keys: &["targeting_id"],
+        today: None,
+    },
+    DiffSpec {
+        media_id: 2,
+        cate: Category::Audience,
+        account: "account_id",
+        keys: &["audience_id"],
+        today: None,
+    },
+    DiffSpec {
+        media_id: 2,
+        cate: Category::Advertiser,
+        account: "uid",
+        keys: &["uid"],
+        today: None,
+    },
+    DiffSpec {
+        media_id: 2,
+        cate: Category::Balance,
+        account: "account_id",
+        keys: &["fund_type"],
+        today: Some("Report_Date"),
+    },
+    // TT
+    DiffSpec {
+        media_id: 4,
+        cate: Category::Project,
+        account: "advertiser_id",
+        keys: &["project_id"],
+        today: None,
+    },
+    DiffSpec {
+        media_id: 4,
+        cate: Category::Promotion,
+        account: "advertiser_id",
+        keys: &["promotion_id"],
+        today: None,
+    },
+    DiffSpec {
+        media_id: 4,
+        cate: Category::AdvertiserHourlyReport,
+        account: "advertiser_id",
+        keys: &["date", "hour"],
+        today: None,
+    },
+    DiffSpec {
+        media_id: 4,
+        cate: Category::ProjectHourlyReport,
+        account: "advertiser_id",
+        keys: &["date", "hour", "project_id"],
+        today: None,
+    },
+    DiffSpec {
+        media_id: 4,
+        cate: Category::PromotionHourlyReport,
+        account: "advertiser_id",
+        keys: &["date", "hour", "promotion_id"],
+        today: None,
+    },
+    DiffSpec {
+        media_id: 4,
+        cate: Category::Balance,
+        account: "advertiser_id",
+        keys: &["advertiser_id"],
+        today: Some("Report_Date"),
+    },
+    DiffSpec {
+        media_id: 4,
+        cate: Category::Budget,
+        account: "advertiser_id",
+        keys: &["advertiser_id"],
+        today: Some("date"),
+    },
+    DiffSpec {
+        media_id: 4,
+        cate: Category::RtaInfo,
+        account: "advertiser_id",
+        keys: &["advertiser_id"],
+        today: None,
+    },
+    // KS
+    DiffSpec {
+        media_id: 5,
+        cate: Category::Campaign,
+        account: "advertiser_id",
+        keys: &["campaign_id"],
+        today: None,
+    },
+    DiffSpec {
+        media_id: 5,
+        cate: Category::Unit,
+        account: "advertiser_id",
+        keys: &["unit_id"],
+        today: None,
+    },
+    DiffSpec {
+        media_id: 5,
+        cate: Category::Creative,
+        account: "advertiser_id",
+        keys: &["creative_id"],
+        today: None,
+    },
+    DiffSpec {
+        media_id: 5,
+        cate: Category::AccountHourlyReport,
+        account: "advertiser_id",
+        keys: &["date", "hour"],
+        today: None,
+    },
+    DiffSpec {
+        media_id: 5,
+        cate: Category::UnitHourlyReport,
+        account: "advertiser_id",
+        keys: &["date", "hour", "unit_id"],
+        today: None,
+    },
+    DiffSpec {
+        media_id: 5,
+        cate: Category::Balance,
+        account: "advertiser_id",
+        keys: &["advertiser_id"],
+        today: Some("Report_Date"),
+    },
+    // Baidu
+    DiffSpec {
+        media_id: 1,
+        cate: Category::Account,
+        account: "user_id",
+        keys: &["user_id"],
+        today: None,
+    },
+    DiffSpec {
+        media_id: 1,
+        cate: Category::Campaign,
+        account: "user_id",
+        keys: &["campaign_id"],
+        today: None,
+    },
+    DiffSpec {
+        media_id: 1,
+        cate: Category::Adgroup,
+        account: "user_id",
+        keys: &["adgroup_id"],
+        today: None,
+    },
+    DiffSpec {
+        media_id: 1,
+        cate: Category::Keyword,
+        account: "user_id",
+        keys: &["keyword_id"],
+        today: None,
+    },
+    DiffSpec {
+        media_id: 1,
+        cate: Category::Creative,
+        account: "user_id",
+        keys: &["creative_id"],
+        today: None,
+    },
+    DiffSpec {
+        media_id: 1,
+        cate: Category::AccountDailyReport,
+        account: "user_id",
+        keys: &["date"],
+        today: None,
+    },
+    DiffSpec {
+        media_id: 1,
+        cate: Category::KeywordDailyReport,
+        account: "user_id",
+        keys: &["date", "keyword_id"],
+        today: None,
+    },
+];
+
+fn spec(verify: &Verify) -> Option<&'static DiffSpec> {
+    DIFF_SPECS
+        .iter()
+        .find(|spec| spec.media_id == verify.media_id && spec.cate == verify.cate)
+}
+
+// Api rows of each verify in flight, keyed by verify id, until its result is written. Rows are
+// held by column, as the upsert of the table would have stored them.
+type Columns = Map<String, Value>;
+
+static PENDING: LazyLock<Mutex<HashMap<String, Vec<Columns>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+// Collects rows instead of writing them, so a dry run leaves every table untouched.
+pub struct DiffSink;
+
+impl Sink for DiffSink {
+    fn write(&self, verify: &Verify, batch: &dyn Batch) -> Result<()> {
+        let columns = upsert::columns(sink::table(verify)?.sql);
+        let rows = batch
+            .params()
+            .into_iter()
+            .map(|params| stored_as(&columns, params))
+            .collect::<Vec<_>>();
+        let mut pending = PENDING.lock().unwrap_or_else(|e| e.into_inner());
+        pending.entry(verify.id.clone()).or_default().extend(rows);
+        Ok(())
+    }
+}
+
+// The row an upsert would store: each column takes its param with the conversions `Row::params`
+// applied. Columns computed in sql, e.g. `DATE(FROM_UNIXTIME(:created_time))`, are not compared.
+fn stored_as(columns: &[(String, String)], params: Params) -> Map<String, Value> {
+    let Params::Named(mut params) = params else {
+        return Map::new();
+    };
+    columns
+        .iter()
+        .filter_map(|(column, name)| {
+            let value = params.remove(name.as_bytes())?;
+            Some((column.clone(), json(value)))
+        })
+        .collect()
+}
+
+// Takes the rows collected for `verify` and compares them with the stored ones. Returns None for
+// categories that can not be read back, e.g. TT accounts whose sync is a plain UPDATE, their
+// result is reported as not comparable.
+pub fn finish(verify: &Verify) -> Result<Option<Diff>> {
+    let api = PENDING
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .remove(&verify.id)
+        .unwrap_or_default();
+    let (Some(spec), Ok(table)) = (spec(verify), sink::table(verify)) else {
+        warn!(
+            "{} Of Media {} Is Not Comparable, {} Rows Dropped",
+            verify.cate,
+            verify.media_id,
+            api.len()
+        );
+        return Ok(None);
+    };
+    let columns = upsert::columns(table.sql)
+        .into_iter()
+        .map(|(column, _)| column)
+        .collect::<Vec<_>>();
+    let db = stored(table, spec, &columns, verify.account_id, &api)?;
+    let diff = compare(
+        table.name,
+        spec.keys,
+        &api,
+        &db,
+        config().sink.float_tolerance,
+    );
+    info!(
+        "Diff Of {} For {}: {} Api Rows, {} Stored, {} Missing, {} Extra, {} Changed",
+        table.name,
+        verify.account_id,
+        diff.api_rows,
+        diff.db_rows,
+        diff.missing,
+        diff.extra,
+        diff.changed
+    );
+    Ok(Some(diff))
+}
+
+// Drops the rows of a verify that failed, its result carries the error instead.
+pub fn discard(verify: &Verify) {
+    PENDING
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .remove(&verify.id);
+}
+
+// Reads the compared columns of the account's rows, for reports only on the dates in `api`.
+fn stored(
+    table: &Table,
+    spec: &DiffSpec,
+    columns: &[String],
+    account_id: u64,
+    api: &[Map<String, Value>],
+) -> Result<Vec<Map<String, Value>>> {
+    if columns.is_empty() {
+        return Ok(vec![]);
+    }
+    let mut sql = format!(
+        "SELECT {} FROM `{}` WHERE `{}` = ?",
+        columns
+            .iter()
+            .map(|column| format!("`{}`", column))
+            .collect::<Vec<_>>()
+            .join(", "),
+        table.name,
+        spec.account
+    );
+    let mut params = vec![mysql::Value::from(account_id)];
+    if spec.keys.contains(&"date") {
+        let dates = api
+            .iter()
+            .filter_map(|row| row.get("date").and_then(scalar))
+            .collect::<BTreeSet<_>>();
+        if dates.is_empty() {
+            return Ok(vec![]);
+        }
+        sql.push_str(&format!(
+            " AND `date` IN ({})",
+            vec!["?"; dates.len()].join(", ")
+        ));
+        params.extend(dates.into_iter().map(mysql::Value::from));
+    }
+    if let Some(today) = spec.today {
+        sql.push_str(&format!(" AND `{}` = CURDATE()", today));
+    }
+    let mut con = match table.db {
+        Db::Tidb => TIDB_POOL.get_conn()?,
+        Db::Core => CORE_POOL.get_conn()?,
+    };
+    let rows: Vec<Row> = con.exec(sql, Params::from(params))?;
+    Ok(rows
+        .into_iter()
+        .map(|row| {
+            let columns = row.columns();
+            columns
+                .iter()
+                .map(|column| column.name_str().into_owned())
+                .zip(row.unwrap().into_iter().map(json))
+                .collect()
+        })
+        .collect())
+}
+
+fn json(value: mysql::Value) -> Value {
+    match value {
+        mysql::Value::NULL => Value::Null,
+        mysql::Value::Bytes(bytes) => Value::String(String::from_utf8_lossy(&bytes).into_owned()),
+        mysql::Value::Int(v) => Value::from(v),
+        mysql::Value::UInt(v) => Value::from(v),
+        mysql::Value::Float(v) => Value::from(v),
+        mysql::Value::Double(v) => Value::from(v),
+        mysql::Value::Date(y, m, d, 0, 0, 0, 0) => {
+            Value::String(format!("{:04}-{:02}-{:02}", y, m, d))
+        }
+        mysql::Value::Date(y, m, d, h, i, s, _) => Value::String(format!(
+            "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+            y, m, d, h, i, s
+        )),
+        mysql::Value::Time(neg, d, h, i, s, _) => Value::String(format!(
+            "{}{:02}:{:02}:{:02}",
+            if neg { "-" } else { "" },
+            d * 24 + h as u32,
+            i,
+            s
+        )),
+    }
+}
+
+fn scalar(value: &Value) -> Option<String> {
+    match value {
+        Value::Null => None,
+        Value::String(s) => Some(s.trim().to_string()),
+        Value::Bool(b) => Some(String::from(if *b { "1" } else { "0" })),
+        other => Some(other.to_string()),
+    }
+}
+
+fn key(row: &Map<String, Value>, keys: &[&str]) -> String {
+    keys.iter()
+        .map(|column| row.get(*column).and_then(scalar).unwrap_or_default())
+        .collect::<Vec<_>>()
+        .join("|")
+}
+
+// Numbers match within `tolerance`, json columns match by content and empty strings match null.
+fn same(api: &Value, db: &Value, tolerance: f64) -> bool {
+    if let (Value::String(api), Value::String(db)) = (api, db)
+        && let (Ok(api @ (Value::Array(_) | Value::Object(_))), Ok(db)) = (
+            serde_json::from_str::<Value>(api),
+            serde_json::from_str::<Value>(db),
+        )
+    {
+        return api == db;
+    }
+    match (scalar(api), scalar(db)) {
+        (None, None) => true,
+        (Some(s), None) | (None, Some(s)) => s.is_empty(),
+        (Some(a), Some(b)) => match (a.parse::<f64>(), b.parse::<f64>()) {
+            (Ok(a), Ok(b)) => (a - b).abs() <= tolerance,
+            _ => a == b,
+        },
+    }
+}
+
+fn record(diff: &mut Diff, entry: DiffEntry) {
+    if diff.entries.len() < MAX_ENTRIES {
+        diff.entries.push(entry);
+    }
+}
+
+fn compare(
+    table: &str,
+    keys: &[&str],
+    api: &[Map<String, Value>],
+    db: &[Map<String, Value>],
+    tolerance: f64,
+) -> Diff {
+    let api_rows = api
+        .iter()
+        .map(|row| (key(row, keys), row))
+        .collect::<BTreeMap<_, _>>();
+    let db_rows = db
+        .iter()
+        .map(|row| (key(row, keys), row))
+        .collect::<BTreeMap<_, _>>();
+    let mut diff = Diff {
+        table: table.to_string(),
+        api_rows: api.len() as u64,
+        db_rows: db.len() as u64,
+        ..Default::default()
+    };
+    for (key, row) in &api_rows {
+        let Some(stored) = db_rows.get(key) else {
+            diff.missing += 1;
+            record(
+                &mut diff,
+                DiffEntry {
+                    kind: DiffKind::Missing,
+                    key: key.clone(),
+                    field: None,
+                    api: Some(Value::Object((*row).clone())),
+                    db: None,
+                },
+            );
+            continue;
+        };
+        for (column, value) in row.iter() {
+            match stored.get(column) {
+                Some(stored) if !same(value, stored, tolerance) => {
+                    diff.changed += 1;
+                    record(
+                        &mut diff,
+                        DiffEntry {
+                            kind: DiffKind::Changed,
+                            key: key.clone(),
+                            field: Some(column.clone()),
+                            api: Some(value.clone()),
+                            db: Some(stored.clone()),
+                        },
+                    );
+                }
+                _ => {}
+            }
+        }
+    }
+    for (key, row) in &db_rows {
+        if !api_rows.contains_key(key) {
+            diff.extra += 1;
+            record(
+                &mut diff,
+                DiffEntry {
+                    kind: DiffKind::Extra,
+                    key: key.clone(),
+                    field: None,
+                    api: None,
+                    db: Some(Value::Object((*row).clone())),
+                },
+            );
+        }
+    }
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mysql::params;
+    use serde_json::json;
+
+    fn row(value: Value) -> Columns {
+        match value {
+            Value::Object(map) => map,
+            _ => panic!("expected an object"),
+        }
+    }
+
+    #[test]
+    fn same_compares_numbers_json_and_nulls() {
+        assert!(same(&json!(1.0), &json!("1.00001"), 0.001));
+        assert!(!same(&json!(1.0), &json!(1.1), 0.001));
+        assert!(same(
+            &json!("{\"a\": [1, 2]}"),
+            &json!("{\"a\":[1,2]}"),
+            0.0
+        ));
+        assert!(!same(&json!("{\"a\": 1}"), &json!("{\"a\": 2}"), 0.0));
+        assert!(same(&json!(""), &Value::Null, 0.0));
+        assert!(same(&json!(true), &json!(1), 0.0));
+        assert!(!same(&json!("on"), &json!("off"), 0.0));
+    }
+
+    #[test]
+    fn stored_as_maps_params_to_columns() {
+        let columns = vec![
+            (String::from("advertiser_id"), String::from("advertiser_id")),
+            (String::from("stat_date"), String::from("date")),
+            (String::from("charge"), String::from("charge")),
+        ];
+        let stored = stored_as(
+            &columns,
+            params! {
+                "advertiser_id" => 42u64,
+                "date" => "2024-05-01",
+                "charge" => 12.5,
+                "unused" => 1,
+            },
+        );
+        assert_eq!(
+            Value::Object(stored),
+            json!({ "advertiser_id": 42, "stat_date": "2024-05-01", "charge": 12.5 })
+        );
+    }
+
+    #[test]
+    fn compare_counts_missing_extra_and_changed() {
+        let api = vec![
+            row(json!({ "date": "2024-05-01", "unit_id": 1, "charge": 1.0 })),
+            row(json!({ "date": "2024-05-01", "unit_id": 2, "charge": 2.0 })),
+        ];
+        let db = vec![
+            row(json!({ "date": "2024-05-01", "unit_id": "1", "charge": 1.5 })),
+            row(json!({ "date": "2024-05-01", "unit_id": 3, "charge": 3.0 })),
+        ];
+        let diff = compare(
+            "synrpt_ks_unit_hourly",
+            &["date", "unit_id"],
+            &api,
+            &db,
+            0.01,
+        );
+        assert_eq!((diff.api_rows, diff.db_rows), (2, 2));
+        assert_eq!((diff.missing, diff.extra, diff.changed), (1, 1, 1));
+        let changed = diff
+            .entries
+            .iter()
+            .find(|entry| matches!(entry.kind, DiffKind::Changed))
+            .unwrap();
+        assert_eq!(changed.key, "2024-05-01|1");
+        assert_eq!(changed.field.as_deref(), Some("charge"));
+    }
+
+    #[test]
+    fn identical_rows_have_no_diff() {
+        let api = vec![row(
+            json!({ "campaign_id": 7, "budget": 100.0, "status": 1 }),
+        )];
+        let db = vec![row(
+            json!({ "campaign_id": 7, "budget": "100.00", "status": 1 }),
+        )];
+        let diff = compare("synads_ks_campaign", &["campaign_id"], &api, &db, 0.001);
+        assert_eq!((diff.missing, diff.extra, diff.changed), (0, 0, 0));
+        assert!(diff.entries.is_empty());
+    }
+
+    #[test]
+    fn spec_keys_are_upserted_columns() {
+        for spec in DIFF_SPECS {
+            let verify = Verify {
+                id: String::new(),
+                cate: spec.cate,
+                media_id: spec.media_id,
+                account_id: 0,
+                url: String::new(),
+                body: None,
+                attempt: 0,
+                pages: None,
+                raw: String::new(),
+            };
+            let table = sink::table(&verify).unwrap();
+            let columns = upsert::columns(table.sql)
+                .into_iter()
+                .map(|(column, _)| column)
+                .collect::<Vec<_>>();
+            for column in spec.keys.iter().chain([&spec.account]) {
+                assert!(
+                    columns.iter().any(|c| c == column),
+                    "{} has no column {}",
+                    table.name,
+                    column
+                );
+            }
+        }
+    }
+}
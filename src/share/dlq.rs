@@ -0,0 +1,240 @@
+use crate::model::error::{Error, Result};
+use crate::model::message::{DeadLetter, Verify};
+use crate::share::common::{REDIS_CLUSTER, redis, slot_prefix};
+use crate::share::config::{SourceKind, config};
+use chrono::Local;
+use clap::Subcommand;
+use log::{error, info, warn};
+use r2d2::PooledConnection;
+use redis::cluster::ClusterClient;
+use redis::cluster_async::ClusterConnection;
+use redis::{AsyncCommands, Commands, Script};
+use std::sync::LazyLock;
+use std::time::Duration;
+use tokio::time::interval;
+
+const PROMOTE_BATCH: usize = 100;
+
+static PROMOTE_SCRIPT: LazyLock<Script> = LazyLock::new(|| {
+    Script::new(
+        r#"
+local items = redis.call('ZRANGEBYSCORE', KEYS[1], '-inf', ARGV[1], 'LIMIT', 0, tonumber(ARGV[2]))
+for _, item in ipairs(items) do
+    redis.call('ZREM', KEYS[1], item)
+    if ARGV[3] == '' then
+        redis.call('RPUSH', KEYS[2], item)
+    else
+        redis.call('XADD', KEYS[2], '*', ARGV[3], item)
+    end
+end
+return #items
+"#,
+    )
+});
+
+#[derive(Subcommand, Debug)]
+pub enum DlqAction {
+    List {
+        #[arg(long, default_value_t = 20, value_parser = clap::value_parser!(u32).range(1..))]
+        limit: u32,
+    },
+    Purge,
+    Replay {
+        #[arg(long)]
+        count: Option<usize>,
+    },
+}
+
+struct Target {
+    key: String,
+    field: Option<String>,
+}
+
+impl Target {
+    fn current() -> Self {
+        let config = config();
+        match config.source.kind {
+            SourceKind::RedisStream => Target {
+                key: config.source.stream_key.clone(),
+                field: Some(config.source.field.clone()),
+            },
+            _ => Target {
+                key: config.queue.verify_key.clone(),
+                field: None,
+            },
+        }
+    }
+
+    // Retries are pushed back onto the redis queue, which the file source never reads.
+    fn retries() -> bool {
+        config().source.kind != SourceKind::File
+    }
+
+    fn delay_key(&self) -> String {
+        format!("{}:delay", slot_prefix(&self.key))
+    }
+
+    fn dead_key(&self) -> String {
+        format!("{}:dead", slot_prefix(&self.key))
+    }
+
+    fn rejected_key(&self) -> String {
+        format!("{}:rejected", slot_prefix(&self.key))
+    }
+
+    fn push(&self, conn: &mut PooledConnection<ClusterClient>, payload: &str) -> Result<()> {
+        match &self.field {
+            Some(field) => {
+                let _: String = conn.xadd(&self.key, "*", &[(field, payload)])?;
+            }
+            None => {
+                let _: usize = conn.rpush(&self.key, payload)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+pub async fn schedule(
+    conn: &mut ClusterConnection,
+    verify: &Verify,
+    err: &Error,
+    failed_pages: Vec<u64>,
+) -> Result<()> {
+    let retry = &config().retry;
+    let target = Target::current();
+    let attempt = verify.attempt + 1;
+    let pending = Verify {
+        pages: if failed_pages.is_empty() {
+            verify.pages.clone()
+        } else {
+            Some(failed_pages)
+        },
+        ..verify.clone()
+    };
+    if attempt >= retry.max_attempts || !err.is_retryable() || !Target::retries() {
+        let letter = DeadLetter {
+            verify: pending,
+            error_kind: err.kind().to_string(),
+            message: err.to_string(),
+            failed_at: Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+        };
+        let _: usize = conn
+            .lpush(target.dead_key(), serde_json::to_string(&letter)?)
+            .await?;
+        warn!(
+            "Verify {} Dead Lettered After {} Attempts",
+            verify.id, attempt
+        );
+        return Ok(());
+    }
+    let delay = retry
+        .base_delay_secs
+        .saturating_mul(1 << verify.attempt.min(20))
+        .min(retry.max_delay_secs);
+    let due = Local::now().timestamp() + delay as i64;
+    let next = Verify { attempt, ..pending };
+    let _: usize = conn
+        .zadd(target.delay_key(), serde_json::to_string(&next)?, due)
+        .await?;
+    info!(
+        "Verify {} Retry {} Scheduled In {}s",
+        verify.id, attempt, delay
+    );
+    Ok(())
+}
+
+// Messages without even an id to report a result under are kept as they came, for inspection.
+pub async fn reject(conn: &mut ClusterConnection, raw: &str) -> Result<()> {
+    let _: usize = conn.lpush(Target::current().rejected_key(), raw).await?;
+    warn!("Unparsable Message Moved To The Rejected List");
+    Ok(())
+}
+
+async fn promote(target: &Target) -> Result<usize> {
+    let mut conn = redis().await?;
+    let moved = PROMOTE_SCRIPT
+        .key(target.delay_key())
+        .key(&target.key)
+        .arg(Local::now().timestamp())
+        .arg(PROMOTE_BATCH)
+        .arg(target.field.as_deref().unwrap_or_default())
+        .invoke_async(&mut conn)
+        .await?;
+    Ok(moved)
+}
+
+pub fn spawn_scheduler() {
+    if !Target::retries() {
+        return;
+    }
+    let poll = Duration::from_millis(config().retry.poll_ms);
+    tokio::spawn(async move {
+        let target = Target::current();
+        let mut ticker = interval(poll);
+        loop {
+            ticker.tick().await;
+            loop {
+                match promote(&target).await {
+                    Ok(moved) if moved == PROMOTE_BATCH => continue,
+                    Ok(_) => break,
+                    Err(err) => {
+                        error!("Promote Delayed Verifies Failed Reason {}", err);
+                        break;
+                    }
+                }
+            }
+        }
+    });
+}
+
+pub fn run(action: DlqAction) -> Result<()> {
+    let target = Target::current();
+    let dead_key = target.dead_key();
+    let mut conn = REDIS_CLUSTER.get()?;
+    match action {
+        DlqAction::List { limit } => {
+            let total: usize = conn.llen(&dead_key)?;
+            let delayed: usize = conn.zcard(target.delay_key())?;
+            let rejected: usize = conn.llen(target.rejected_key())?;
+            println!(
+                "dead letters: {}, delayed retries: {}, rejected messages: {}",
+                total, delayed, rejected
+            );
+            let items: Vec<String> = conn.lrange(&dead_key, 0, limit as isize - 1)?;
+            for item in items {
+                println!("{}", item);
+            }
+        }
+        DlqAction::Purge => {
+            let total: usize = conn.llen(&dead_key)?;
+            let _: usize = conn.del(&dead_key)?;
+            println!("purged {} dead letters", total);
+        }
+        DlqAction::Replay { .. } if !Target::retries() => {
+            return Err(Error::Config(String::from(
+                "dlq replay needs a redis source, the file source never reads the verify queue",
+            )));
+        }
+        DlqAction::Replay { count } => {
+            let mut replayed = 0;
+            while count.is_none_or(|count| replayed < count) {
+                let Some(item) = conn.rpop::<_, Option<String>>(&dead_key, None)? else {
+                    break;
+                };
+                let letter = serde_json::from_str::<DeadLetter>(&item)?;
+                let verify = Verify {
+                    attempt: 0,
+                    ..letter.verify
+                };
+                if let Err(err) = target.push(&mut conn, &serde_json::to_string(&verify)?) {
+                    let _: usize = conn.rpush(&dead_key, &item)?;
+                    return Err(err);
+                }
+                replayed += 1;
+            }
+            println!("replayed {} dead letters", replayed);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,522 @@
+use crate::model::category::Category;
+use crate::model::error::Result;
+use crate::model::message::Verify;
+use crate::share::common::{Limiter, redis, slot_prefix};
+use crate::share::config::config;
+use governor::clock::DefaultClock;
+use governor::state::{InMemoryState, NotKeyed};
+use governor::{Jitter, Quota, RateLimiter};
+use log::{info, warn};
+use redis::{AsyncCommands, Script};
+use std::collections::HashMap;
+use std::num::NonZeroU32;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, LazyLock, Mutex};
+use std::time::{Duration, Instant};
+use tokio::time::{sleep, timeout};
+
+// GCRA with a burst of one: KEYS[1] holds the theoretical arrival time in microseconds of redis
+// server time, so every instance shares one clock. KEYS[2] holds the rate a throttle code cut the
+// qps in ARGV[1] to, recovering by ARGV[2] of it per second. Returns how long the caller must wait,
+// 0 when the request is admitted and -1 when it is admitted at a rate that just fully recovered.
+const GCRA_SCRIPT: &str = r#"
+local now = redis.call('TIME')
+now = tonumber(now[1]) * 1000000 + tonumber(now[2])
+local freq = tonumber(ARGV[1])
+local rate = freq
+local cut = redis.call('HMGET', KEYS[2], 'rate', 'at')
+if cut[1] then
+    rate = math.min(freq, tonumber(cut[1]) + freq * tonumber(ARGV[2]) * (now - tonumber(cut[2])) / 1000000)
+end
+local interval = 1000000 / rate
+local tat = tonumber(redis.call('GET', KEYS[1]) or now)
+if tat > now then
+    return math.ceil(tat - now)
+end
+redis.call('SET', KEYS[1], string.format('%.0f', now + interval), 'PX', math.ceil(interval / 1000) + 1000)
+if cut[1] and rate >= freq then
+    redis.call('DEL', KEYS[2])
+    return -1
+end
+return 0
+"#;
+
+// Halves the shared rate in KEYS[1] of the qps in ARGV[1], recovering by ARGV[2] of it per second,
+// down to ARGV[3] of it. Instances throttled by the same burst cut it once a second at most.
+// Returns the rate.
+const CUT_SCRIPT: &str = r#"
+local now = redis.call('TIME')
+now = tonumber(now[1]) * 1000000 + tonumber(now[2])
+local freq = tonumber(ARGV[1])
+local step = tonumber(ARGV[2])
+local rate = freq
+local cut = redis.call('HMGET', KEYS[1], 'rate', 'at')
+if cut[1] then
+    local at = tonumber(cut[2])
+    rate = math.min(freq, tonumber(cut[1]) + freq * step * (now - at) / 1000000)
+    if now - at < 1000000 then
+        return string.format('%.4f', rate)
+    end
+end
+rate = math.max(rate / 2, freq * tonumber(ARGV[3]))
+redis.call('HSET', KEYS[1], 'rate', string.format('%.4f', rate), 'at', string.format('%.0f', now))
+redis.call('PEXPIRE', KEYS[1], math.ceil((freq - rate) / (freq * step) * 1000) + 1000)
+return string.format('%.4f', rate)
+"#;
+
+static GCRA: LazyLock<Script> = LazyLock::new(|| Script::new(GCRA_SCRIPT));
+
+static CUT: LazyLock<Script> = LazyLock::new(|| Script::new(CUT_SCRIPT));
+
+// A slow redis must not hold requests back longer than the local fallback would.
+const REDIS_TIMEOUT: Duration = Duration::from_millis(200);
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct LimitKey {
+    pub media_id: u8,
+    pub endpoint: String,
+    pub account_id: Option<u64>,
+}
+
+static LIMITERS: LazyLock<Mutex<HashMap<LimitKey, Limiter>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+// Per-account limiters nobody holds are dropped once the map grows past this.
+const MAX_IDLE_LIMITERS: usize = 1024;
+
+// The endpoints each category calls, as `endpoint()` names them. Rate limit keys must name one.
+pub const ENDPOINTS: &[(u8, Category, &str)] = &[
+    // Baidu
+    (1, Category::Account, "AccountService/getAccountInfo"),
+    (1, Category::Campaign, "CampaignService/getCampaign"),
+    (1, Category::Adgroup, "AdgroupService/getAdgroup"),
+    (1, Category::Keyword, "KeywordService/getWord"),
+    (1, Category::Creative, "CreativeService/getCreative"),
+    (
+        1,
+        Category::AccountDailyReport,
+        "OpenApiReportService/getReportData",
+    ),
+    (
+        1,
+        Category::KeywordDailyReport,
+        "OpenApiReportService/getReportData",
+    ),
+    // GDT
+    (2, Category::AdgroupV3, "adgroups/get"),
+    (2, Category::DynamicCreativeV3, "dynamic_creatives/get"),
+    (2, Category::AdgroupDailyRequestPartV3, "daily_reports/get"),
+    (
+        2,
+        Category::AdvertiserDailyReportingPartV3,
+        "daily_reports/get",
+    ),
+    (
+        2,
+        Category::DynamicCreativeDailyRequestPartV3,
+        "daily_reports/get",
+    ),
+    (2, Category::AdvertiserReportingPartV3, "hourly_reports/get"),
+    (2, Category::Advertiser, "advertiser/get"),
+    (2, Category::Target, "targetings/get"),
+    (2, Category::Audience, "custom_audiences/get"),
+    (2, Category::Balance, "funds/get"),
+    // TT
+    (4, Category::Project, "project/list"),
+    (4, Category::Promotion, "promotion/list"),
+    (4, Category::Balance, "fund/get"),
+    (4, Category::Budget, "budget/get"),
+    (4, Category::RtaInfo, "rta/get_info"),
+    (4, Category::Account, "advertiser/info"),
+    (4, Category::AdvertiserHourlyReport, "custom/get"),
+    (4, Category::ProjectHourlyReport, "custom/get"),
+    (4, Category::PromotionHourlyReport, "custom/get"),
+    // KS
+    (5, Category::Campaign, "campaign/list"),
+    (5, Category::Unit, "unit/list"),
+    (5, Category::Creative, "creative/list"),
+    (5, Category::AccountHourlyReport, "report/account_report"),
+    (5, Category::UnitHourlyReport, "report/unit_report"),
+    (5, Category::Balance, "fund/get_balance"),
+];
+
+fn known(media_id: u8, endpoint: &str) -> bool {
+    ENDPOINTS
+        .iter()
+        .any(|(media, _, known)| *media == media_id && *known == endpoint)
+}
+
+// Checks a `media_id:endpoint` rate limit key. Keys from before limits were per endpoint name a
+// category, the error points at the key that replaces it.
+pub fn check_key(key: &str) -> std::result::Result<(), String> {
+    let Some((media_id, endpoint)) = key
+        .split_once(':')
+        .and_then(|(media, endpoint)| Some((media.parse::<u8>().ok()?, endpoint)))
+    else {
+        return Err(String::from("must be media_id:endpoint"));
+    };
+    if known(media_id, endpoint) {
+        return Ok(());
+    }
+    let replacement = endpoint.parse::<Category>().ok().and_then(|cate| {
+        ENDPOINTS
+            .iter()
+            .find(|(media, known, _)| *media == media_id && *known == cate)
+    });
+    match replacement {
+        Some((_, _, endpoint)) => Err(format!(
+            "names a category, limits are keyed by endpoint: use \"{}:{}\"",
+            media_id, endpoint
+        )),
+        None => Err(format!(
+            "is not an endpoint of media {}, known are {}",
+            media_id,
+            ENDPOINTS
+                .iter()
+                .filter(|(media, _, _)| *media == media_id)
+                .map(|(_, _, endpoint)| *endpoint)
+                .collect::<std::collections::BTreeSet<_>>()
+                .into_iter()
+                .collect::<Vec<_>>()
+                .join(", ")
+        )),
+    }
+}
+
+// Throttle codes halve the effective rate down to this share of the configured qps, and every
+// quiet second restores RECOVERY_STEP of it.
+const FLOOR: f64 = 0.05;
+const RECOVERY_STEP: f64 = 0.05;
+
+pub struct SharedLimiter {
+    key: String,
+    rate_key: String,
+    distributed: bool,
+    freq: f64,
+    local: RateLimiter<NotKeyed, InMemoryState, DefaultClock>,
+    degraded: AtomicBool,
+    aimd: Mutex<Aimd>,
+}
+
+struct Aimd {
+    rate: f64,
+    updated: Instant,
+    next: Instant,
+}
+
+enum Pace {
+    Free,
+    Restored,
+    Wait(Duration),
+}
+
+impl Aimd {
+    // Additive increase, applied lazily for the time passed since the last update.
+    fn restore(&mut self, freq: f64, now: Instant) {
+        let secs = now.duration_since(self.updated).as_secs_f64();
+        self.rate = (self.rate + freq * RECOVERY_STEP * secs).min(freq);
+        self.updated = now;
+    }
+
+    // Multiplicative decrease, never below `FLOOR` of the configured qps.
+    fn cut(&mut self, freq: f64, now: Instant) -> f64 {
+        self.restore(freq, now);
+        self.rate = (self.rate / 2.0).max(freq * FLOOR);
+        self.rate
+    }
+
+    // Books the next slot at the throttled rate, `Restored` once on the way back to `freq`.
+    fn pace(&mut self, freq: f64, now: Instant) -> Pace {
+        let throttled = self.rate < freq;
+        self.restore(freq, now);
+        if self.rate >= freq {
+            return if throttled {
+                Pace::Restored
+            } else {
+                Pace::Free
+            };
+        }
+        let at = self.next.max(now);
+        self.next = at + Duration::from_secs_f64(1.0 / self.rate);
+        Pace::Wait(at - now)
+    }
+}
+
+impl SharedLimiter {
+    fn new(key: String, distributed: bool, freq: usize) -> Self {
+        let freq = NonZeroU32::new(freq as u32).unwrap();
+        let now = Instant::now();
+        SharedLimiter {
+            rate_key: format!("{}:rate", key),
+            key,
+            distributed,
+            freq: freq.get() as f64,
+            local: RateLimiter::direct(
+                Quota::per_second(freq).allow_burst(NonZeroU32::new(1).unwrap()),
+            ),
+            degraded: AtomicBool::new(false),
+            aimd: Mutex::new(Aimd {
+                rate: freq.get() as f64,
+                updated: now,
+                next: now,
+            }),
+        }
+    }
+
+    // Multiplicative decrease on a platform throttle code. The shared rate slows every instance
+    // down, the local one paces this instance while redis is disabled or unreachable.
+    pub async fn throttled(&self) {
+        let mut rate = self
+            .aimd
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .cut(self.freq, Instant::now());
+        if self.distributed {
+            match self.cut().await {
+                Ok(shared) => rate = shared,
+                Err(err) => warn!("Shared Rate Cut Of {} Failed Reason {}", self.key, err),
+            }
+        }
+        warn!(
+            "Throttled {} Rate Cut To {:.2}/{} qps",
+            self.key, rate, self.freq
+        );
+        publish(&self.key, Some(rate)).await;
+    }
+
+    // Waits on the redis bucket shared by every instance, which runs at the shared cut rate, and
+    // on the in-process bucket paced at the local cut rate when redis is disabled or unreachable.
+    pub async fn until_ready(&self) {
+        if self.distributed {
+            let key = &self.key;
+            loop {
+                match self.acquire().await {
+                    Ok(wait) if wait <= 0 => {
+                        if self.degraded.swap(false, Ordering::Relaxed) {
+                            info!("Distributed Limiter {} Recovered", key);
+                        }
+                        if wait < 0 {
+                            info!("Shared Rate Of {} Restored To {} qps", key, self.freq);
+                            publish(key, None).await;
+                        }
+                        return;
+                    }
+                    Ok(wait) => {
+                        let jitter = Duration::from_millis(fastrand::u64(0..100));
+                        sleep(Duration::from_micros(wait as u64) + jitter).await;
+                    }
+                    Err(err) => {
+                        if !self.degraded.swap(true, Ordering::Relaxed) {
+                            warn!(
+                                "Distributed Limiter {} Falling Back To Local Reason {}",
+                                key, err
+                            );
+                        }
+                        break;
+                    }
+                }
+            }
+        }
+        self.local
+            .until_ready_with_jitter(Jitter::up_to(Duration::from_millis(100)))
+            .await;
+        self.pace().await;
+    }
+
+    // Spaces requests at the throttled rate until it has recovered to the configured qps.
+    async fn pace(&self) {
+        let pace = self
+            .aimd
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .pace(self.freq, Instant::now());
+        match pace {
+            Pace::Free => {}
+            Pace::Wait(wait) => sleep(wait).await,
+            Pace::Restored => {
+                info!("Rate Of {} Restored To {} qps", self.key, self.freq);
+                publish(&self.key, None).await;
+            }
+        }
+    }
+
+    async fn acquire(&self) -> Result<i64> {
+        let mut conn = redis().await?;
+        let wait: i64 = timeout(
+            REDIS_TIMEOUT,
+            GCRA.key(&self.key)
+                .key(&self.rate_key)
+                .arg(self.freq)
+                .arg(RECOVERY_STEP)
+                .invoke_async(&mut conn),
+        )
+        .await
+        .map_err(std::io::Error::from)??;
+        Ok(wait)
+    }
+
+    async fn cut(&self) -> Result<f64> {
+        let mut conn = redis().await?;
+        let rate: String = timeout(
+            REDIS_TIMEOUT,
+            CUT.key(&self.rate_key)
+                .arg(self.freq)
+                .arg(RECOVERY_STEP)
+                .arg(FLOOR)
+                .invoke_async(&mut conn),
+        )
+        .await
+        .map_err(std::io::Error::from)??;
+        Ok(rate.parse().unwrap_or(self.freq * FLOOR))
+    }
+}
+
+// The last two path segments name the endpoint, e.g. `adgroups/get` or `project/list`.
+pub fn endpoint(url: &str) -> String {
+    let path = reqwest::Url::parse(url)
+        .map(|url| url.path().to_string())
+        .unwrap_or_else(|_| url.to_string());
+    let mut segments = path.rsplit('/').filter(|segment| !segment.is_empty());
+    let last = segments.next().unwrap_or_default();
+    match segments.next() {
+        Some(parent) => format!("{}/{}", parent, last),
+        None => last.to_string(),
+    }
+}
+
+// Endpoints without a `rate_limit` or `account_rate_limit` entry run at `limiter.default_qps`.
+pub fn endpoint_limiter(verify: &Verify) -> Limiter {
+    let endpoint = endpoint(&verify.url);
+    let (freq, per_account) = config()
+        .quota(verify.media_id, &endpoint)
+        .unwrap_or((config().limiter.default_qps, false));
+    let key = LimitKey {
+        media_id: verify.media_id,
+        endpoint,
+        account_id: per_account.then_some(verify.account_id),
+    };
+    let mut limiters = LIMITERS.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(limiter) = limiters.get(&key) {
+        return Arc::clone(limiter);
+    }
+    if !known(key.media_id, &key.endpoint) {
+        warn!(
+            "Endpoint {} Of Media {} Is Not Known, It Runs At {} qps",
+            key.endpoint, key.media_id, freq
+        );
+    }
+    if limiters.len() >= MAX_IDLE_LIMITERS {
+        limiters.retain(|key, limiter| key.account_id.is_none() || Arc::strong_count(limiter) > 1);
+    }
+    let limiter = Arc::new(SharedLimiter::new(
+        redis_key(&key),
+        config().limiter.distributed,
+        freq,
+    ));
+    limiters.insert(key, Arc::clone(&limiter));
+    limiter
+}
+
+// Hash tagged so the GCRA script reaches the rate key next to it.
+fn redis_key(key: &LimitKey) -> String {
+    let mut name = format!(
+        "{}:{}:{}",
+        config().limiter.key_prefix,
+        key.media_id,
+        key.endpoint
+    );
+    if let Some(account_id) = key.account_id {
+        name = format!("{}:{}", name, account_id);
+    }
+    slot_prefix(&name)
+}
+
+// Throttled limiters are listed with their current rate in the `<key_prefix>:rates` hash, so
+// `HGETALL` shows which endpoints and accounts are being held back.
+async fn publish(key: &str, rate: Option<f64>) {
+    let rates = format!("{}:rates", config().limiter.key_prefix);
+    let rt: Result<()> = async {
+        let mut conn = redis().await?;
+        let _: usize = match rate {
+            Some(rate) => conn.hset(&rates, key, format!("{:.2}", rate)).await?,
+            None => conn.hdel(&rates, key).await?,
+        };
+        Ok(())
+    }
+    .await;
+    if let Err(err) = rt {
+        warn!("Publish Rate Of {} Failed Reason {}", key, err);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn aimd(rate: f64, now: Instant) -> Aimd {
+        Aimd {
+            rate,
+            updated: now,
+            next: now,
+        }
+    }
+
+    #[test]
+    fn cut_halves_down_to_the_floor() {
+        let now = Instant::now();
+        let mut aimd = aimd(10.0, now);
+        assert_eq!(aimd.cut(10.0, now), 5.0);
+        assert_eq!(aimd.cut(10.0, now), 2.5);
+        for _ in 0..10 {
+            aimd.cut(10.0, now);
+        }
+        assert_eq!(aimd.rate, 10.0 * FLOOR);
+    }
+
+    #[test]
+    fn restore_adds_a_step_per_second_up_to_freq() {
+        let now = Instant::now();
+        let mut aimd = aimd(2.0, now);
+        aimd.restore(10.0, now + Duration::from_secs(2));
+        assert!((aimd.rate - (2.0 + 10.0 * RECOVERY_STEP * 2.0)).abs() < 1e-9);
+        aimd.restore(10.0, now + Duration::from_secs(60));
+        assert_eq!(aimd.rate, 10.0);
+    }
+
+    #[test]
+    fn pace_spaces_slots_at_the_cut_rate() {
+        let now = Instant::now();
+        let mut aimd = aimd(10.0, now);
+        assert!(matches!(aimd.pace(10.0, now), Pace::Free));
+        aimd.cut(10.0, now);
+        assert!(matches!(aimd.pace(10.0, now), Pace::Wait(wait) if wait.is_zero()));
+        assert!(
+            matches!(aimd.pace(10.0, now), Pace::Wait(wait) if wait == Duration::from_millis(200))
+        );
+    }
+
+    #[test]
+    fn pace_reports_the_recovery_once() {
+        let now = Instant::now();
+        let mut aimd = aimd(10.0, now);
+        aimd.cut(10.0, now);
+        let later = now + Duration::from_secs(20);
+        assert!(matches!(aimd.pace(10.0, later), Pace::Restored));
+        assert!(matches!(aimd.pace(10.0, later), Pace::Free));
+    }
+
+    #[test]
+    fn the_shared_rate_sits_in_the_slot_of_its_bucket() {
+        let key = redis_key(&LimitKey {
+            media_id: 2,
+            endpoint: String::from("adgroups/get"),
+            account_id: Some(7),
+        });
+        assert_eq!(key, "{marketing_api_verify:limiter:2:adgroups/get:7}");
+        let limiter = SharedLimiter::new(key, true, 10);
+        assert_eq!(
+            limiter.rate_key,
+            "{marketing_api_verify:limiter:2:adgroups/get:7}:rate"
+        );
+    }
+}
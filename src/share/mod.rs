@@ -1 +1,13 @@
+pub mod budget;
 pub mod common;
+pub mod config;
+pub mod diff;
+pub mod dlq;
+pub mod limiter;
+pub mod offload;
+pub mod reconcile;
+pub mod shutdown;
+pub mod sink;
+pub mod template;
+pub mod token;
+pub mod upsert;
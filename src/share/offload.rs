@@ -0,0 +1,26 @@
+use std::future::Future;
+use std::panic;
+use tokio::task::spawn_blocking;
+
+// Runs `f` on the blocking pool once `permit` resolves, keeping the runtime workers free for the
+// fetches in flight. The permit moves into the blocking task: a caller aborted at shutdown can
+// not cancel `f`, so its connection stays counted until `f` returns. A panic in `f` is raised in
+// the caller as if `f` had run inline.
+//
+// Only needs tokio, so `benches/blocking.rs` drives this very function.
+pub async fn offload<P, R>(
+    permit: impl Future<Output = P>,
+    f: impl FnOnce() -> R + Send + 'static,
+) -> R
+where
+    P: Send + 'static,
+    R: Send + 'static,
+{
+    let permit = permit.await;
+    spawn_blocking(move || {
+        let _permit = permit;
+        f()
+    })
+    .await
+    .unwrap_or_else(|err| panic::resume_unwind(err.into_panic()))
+}
@@ -0,0 +1,171 @@
+use crate::model::category::Category;
+use crate::model::error::Result;
+use crate::model::message::Verify;
+use crate::model::result::Discrepancy;
+use crate::share::common::{TIDB_POOL, blocking, redis, slot_prefix};
+use crate::share::config::{ReconcileConfig, config};
+use crate::share::sink::Db;
+use chrono::Local;
+use log::{error, info, warn};
+use mysql::params;
+use mysql::prelude::Queryable;
+use redis::AsyncCommands;
+
+// A cross-level check: `sql` returns (period, parent cost, summed child cost) for :account_id over
+// the last :days days and runs whenever one of `triggers` synced.
+struct Check {
+    name: &'static str,
+    media_id: u8,
+    triggers: &'static [Category],
+    sql: &'static str,
+}
+
+const CHECKS: &[Check] = &[
+    Check {
+        name: "gdt_advertiser_daily_vs_adgroup_daily",
+        media_id: 2,
+        triggers: &[
+            Category::AdvertiserDailyReportingPartV3,
+            Category::AdgroupDailyRequestPartV3,
+        ],
+        sql: "SELECT DATE_FORMAT(a.`date`, '%Y-%m-%d'), a.`cost`, (SELECT SUM(g.`cost`) FROM `synrpt_gdt_adgroup_daily_request_part_v3` g WHERE g.`account_id` = a.`account_id` AND g.`date` = a.`date`) FROM `synrpt_gdt_advertiser_daily_reporting_part_v3` a WHERE a.`account_id` = :account_id AND a.`date` >= DATE_SUB(CURDATE(), INTERVAL :days DAY)",
+    },
+    Check {
+        name: "tt_advertiser_hourly_vs_project_hourly",
+        media_id: 4,
+        triggers: &[
+            Category::AdvertiserHourlyReport,
+            Category::ProjectHourlyReport,
+        ],
+        sql: "SELECT CONCAT(DATE_FORMAT(a.`date`, '%Y-%m-%d'), ' ', a.`hour`), a.`stat_cost`, (SELECT SUM(p.`stat_cost`) FROM `synrpt_tt_project` p WHERE p.`advertiser_id` = a.`advertiser_id` AND p.`date` = a.`date` AND p.`hour` = a.`hour`) FROM `synrpt_tt_advertiser_v2` a WHERE a.`advertiser_id` = :account_id AND a.`date` >= DATE_SUB(CURDATE(), INTERVAL :days DAY)",
+    },
+    Check {
+        name: "tt_advertiser_hourly_vs_promotion_hourly",
+        media_id: 4,
+        triggers: &[
+            Category::AdvertiserHourlyReport,
+            Category::PromotionHourlyReport,
+        ],
+        sql: "SELECT CONCAT(DATE_FORMAT(a.`date`, '%Y-%m-%d'), ' ', a.`hour`), a.`stat_cost`, (SELECT SUM(p.`stat_cost`) FROM `synrpt_tt_promotion` p WHERE p.`advertiser_id` = a.`advertiser_id` AND p.`date` = a.`date` AND p.`hour` = a.`hour`) FROM `synrpt_tt_advertiser_v2` a WHERE a.`advertiser_id` = :account_id AND a.`date` >= DATE_SUB(CURDATE(), INTERVAL :days DAY)",
+    },
+];
+
+// Open discrepancies live in one hash next to the results, `<check>:<account_id>:<period>` to
+// the json of the discrepancy. A period that reconciles on a later run is removed again, so the
+// hash only holds what is still off, e.g. a level whose sync has not caught up yet.
+fn key() -> String {
+    format!("{}:reconcile", slot_prefix(&config().queue.result_key))
+}
+
+// Runs the checks triggered by a successful sync of `verify`. Failures are logged only, the
+// verify itself already succeeded.
+pub async fn after_sync(verify: &Verify) {
+    if !config().reconcile.enabled {
+        return;
+    }
+    for check in CHECKS
+        .iter()
+        .filter(|c| c.media_id == verify.media_id && c.triggers.contains(&verify.cate))
+    {
+        if let Err(err) = run(check, verify.account_id).await {
+            error!(
+                "Reconcile {} For {} Failed Reason {}",
+                check.name, verify.account_id, err
+            );
+        }
+    }
+}
+
+// Levels agree within `tolerance` or `ratio` of the parent, whichever is larger, so rounding in
+// small accounts and late hours in large ones are not flagged.
+fn reconciles(reconcile: &ReconcileConfig, parent: f64, children: f64) -> bool {
+    (parent - children).abs() <= reconcile.tolerance.max(parent.abs() * reconcile.ratio)
+}
+
+async fn run(check: &Check, account_id: u64) -> Result<()> {
+    let reconcile = &config().reconcile;
+    let (sql, days) = (check.sql, reconcile.days);
+    let rows: Vec<(String, Option<f64>, Option<f64>)> = blocking(Db::Tidb, move || {
+        TIDB_POOL.get_conn()?.exec(
+            sql,
+            params! {
+                "account_id" => account_id,
+                "days" => days,
+            },
+        )
+    })
+    .await?;
+    let checked_at = Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+    let mut flagged = vec![];
+    let mut cleared = vec![];
+    for (period, parent, children) in rows {
+        let field = format!("{}:{}:{}", check.name, account_id, period);
+        let (parent, children) = (parent.unwrap_or_default(), children.unwrap_or_default());
+        let delta = parent - children;
+        if reconciles(reconcile, parent, children) {
+            cleared.push(field);
+            continue;
+        }
+        warn!(
+            "Reconcile {} For {} At {} Off By {:.4}: Parent {:.4}, Children {:.4}",
+            check.name, account_id, period, delta, parent, children
+        );
+        let discrepancy = Discrepancy {
+            check: check.name.to_string(),
+            media_id: check.media_id,
+            account_id,
+            period,
+            parent,
+            children,
+            delta,
+            checked_at: checked_at.clone(),
+        };
+        flagged.push((field, serde_json::to_string(&discrepancy)?));
+    }
+    let key = key();
+    let mut conn = redis().await?;
+    if !flagged.is_empty() {
+        let _: () = conn.hset_multiple(&key, &flagged).await?;
+    }
+    if !cleared.is_empty() {
+        let _: usize = conn.hdel(&key, &cleared).await?;
+    }
+    info!(
+        "Reconciled {} For {}: {} Flagged, {} Clean",
+        check.name,
+        account_id,
+        flagged.len(),
+        cleared.len()
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn small_parents_use_the_absolute_tolerance() {
+        let reconcile = ReconcileConfig::default();
+        assert!(reconciles(&reconcile, 10.0, 9.995));
+        assert!(!reconciles(&reconcile, 10.0, 9.98));
+        assert!(reconciles(&reconcile, 0.0, 0.0));
+        assert!(!reconciles(&reconcile, 0.0, 0.5));
+    }
+
+    #[test]
+    fn large_parents_use_the_ratio() {
+        let reconcile = ReconcileConfig::default();
+        assert!(reconciles(&reconcile, 100_000.0, 99_950.0));
+        assert!(reconciles(&reconcile, 100_000.0, 100_100.0));
+        assert!(!reconciles(&reconcile, 100_000.0, 99_850.0));
+    }
+
+    #[test]
+    fn checks_have_triggers_and_bind_their_params() {
+        for check in CHECKS {
+            assert!(!check.triggers.is_empty());
+            assert!(check.sql.contains(":account_id") && check.sql.contains(":days"));
+        }
+    }
+}
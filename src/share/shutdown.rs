@@ -0,0 +1,120 @@
+use crate::model::message::Verify;
+use log::{error, info, warn};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{LazyLock, Mutex};
+use std::time::Duration;
+use tokio::signal::unix::{SignalKind, signal};
+use tokio::sync::Notify;
+use tokio::task::AbortHandle;
+use tokio::time::sleep;
+
+static STOPPING: AtomicBool = AtomicBool::new(false);
+
+static STOP: Notify = Notify::const_new();
+
+// Verifies of the current batch whose result has not been written yet, by id.
+static IN_FLIGHT: LazyLock<Mutex<HashMap<String, Verify>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+// Tasks spawned for the current batch: group syncs, verifies and their pages.
+static TASKS: LazyLock<Mutex<Vec<AbortHandle>>> = LazyLock::new(|| Mutex::new(Vec::new()));
+
+// Flags a stop on the first SIGTERM or SIGINT. A second one exits right away, so an impatient
+// operator can still cut the drain short.
+pub fn spawn_listener() {
+    tokio::spawn(async {
+        let mut term = match signal(SignalKind::terminate()) {
+            Ok(term) => term,
+            Err(err) => {
+                error!("Install SIGTERM Handler Failed Reason {}", err);
+                return;
+            }
+        };
+        let name = tokio::select! {
+            _ = term.recv() => "SIGTERM",
+            _ = tokio::signal::ctrl_c() => "SIGINT",
+        };
+        info!(
+            "Received {}, Stop Fetching And Drain In-Flight Verifies",
+            name
+        );
+        STOPPING.store(true, Ordering::SeqCst);
+        STOP.notify_waiters();
+        tokio::select! {
+            _ = term.recv() => {}
+            _ = tokio::signal::ctrl_c() => {}
+        }
+        warn!(
+            "Received A Second Signal, Exiting With {} Verifies In Flight",
+            IN_FLIGHT.lock().unwrap_or_else(|e| e.into_inner()).len()
+        );
+        std::process::exit(1);
+    });
+}
+
+pub fn stopping() -> bool {
+    STOPPING.load(Ordering::SeqCst)
+}
+
+pub async fn stopped() {
+    let notified = STOP.notified();
+    if stopping() {
+        return;
+    }
+    notified.await;
+}
+
+pub fn track(verifies: &[Verify]) {
+    let mut in_flight = IN_FLIGHT.lock().unwrap_or_else(|e| e.into_inner());
+    for verify in verifies {
+        in_flight.insert(verify.id.clone(), verify.clone());
+    }
+}
+
+pub fn done(verify: &Verify) {
+    IN_FLIGHT
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .remove(&verify.id);
+}
+
+// Those of `verifies` whose result has not been written yet.
+pub fn in_flight(verifies: Vec<Verify>) -> Vec<Verify> {
+    let in_flight = IN_FLIGHT.lock().unwrap_or_else(|e| e.into_inner());
+    verifies
+        .into_iter()
+        .filter(|verify| in_flight.contains_key(&verify.id))
+        .collect()
+}
+
+// Takes what is still in flight. After a batch completed this is only verifies whose result
+// could not be stored, after a drain timed out it is what has to go back to the source.
+pub fn unfinished() -> Vec<Verify> {
+    IN_FLIGHT
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .drain()
+        .map(|(_, verify)| verify)
+        .collect()
+}
+
+pub fn spawned(task: AbortHandle) {
+    let mut tasks = TASKS.lock().unwrap_or_else(|e| e.into_inner());
+    tasks.retain(|task| !task.is_finished());
+    tasks.push(task);
+}
+
+// Aborts the tasks of a batch whose drain ran out and waits until every one has stopped, so none
+// stores a result for a verify that is about to go back to the source. A task inside a blocking
+// write stops once the write returns.
+pub async fn abort_tasks() -> usize {
+    let tasks = std::mem::take(&mut *TASKS.lock().unwrap_or_else(|e| e.into_inner()));
+    for task in &tasks {
+        task.abort();
+    }
+    while tasks.iter().any(|task| !task.is_finished()) {
+        sleep(Duration::from_millis(10)).await;
+    }
+    tasks.len()
+}
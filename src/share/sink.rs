@@ -0,0 +1,624 @@
+use crate::model::category::Category;
+use crate::model::error::{Error, Result};
+use crate::model::message::Verify;
+use crate::share::common::{
+    BAIDU_ACCOUNT_DAILY_REPORT_SQL, BAIDU_ACCOUNT_SQL, BAIDU_ADGROUP_SQL, BAIDU_CAMPAIGN_SQL,
+    BAIDU_CREATIVE_SQL, BAIDU_KEYWORD_DAILY_REPORT_SQL, BAIDU_KEYWORD_SQL, CORE_POOL,
+    GDT_ACCOUNT_DAILY_SQL_TL_REPORTING_V3, GDT_ACCOUNT_HOURLY_SQL_TL_REPORTING_V3,
+    GDT_ADGROUP_DAILY_SQL_TL_REQUEST_V3, GDT_ADGROUP_SQL_V3, GDT_ADVERTISER_SQL, GDT_AUDIENCE_SQL,
+    GDT_BALANCE_SQL, GDT_DYNAMIC_CREATIVE_DAILY_SQL_TL_REQUEST_V3, GDT_DYNAMIC_CREATIVE_SQL_V3,
+    GDT_TARGETING_SQL, KS_ACCOUNT_HOURLY_REPORT_SQL, KS_BALANCE_SQL, KS_CAMPAIGN_SQL,
+    KS_CREATIVE_SQL, KS_UNIT_HOURLY_REPORT_SQL, KS_UNIT_SQL, TIDB_POOL,
+    TT_ADVERTISER_HOURLY_REPORT_SQL, TT_ADVERTISER_SQL, TT_BALANCE_SQL, TT_BUDGET_SQL,
+    TT_PROJECT_HOURLY_REPORT_SQL, TT_PROJECT_SQL, TT_PROMOTION_HOURLY_REPORT_SQL, TT_PROMOTION_SQL,
+    TT_RTA_INFO_SQL, blocking,
+};
+use crate::share::config::{SinkKind, config};
+use crate::share::diff::DiffSink;
+use crate::share::upsert;
+use mysql::Params;
+use serde::Serialize;
+use serde_json::{Value, json};
+#[cfg(test)]
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Db {
+    Tidb,
+    Core,
+}
+
+// A typed api row, e.g. `AdGroup`, `GdtReportV3` or `TtFlat`. `params` maps its fields to the
+// named params of the upsert of its table, converted the way the table stores them.
+pub trait Row: Serialize + Sync {
+    fn params(&self, verify: &Verify) -> Params;
+}
+
+// A page of rows of one verify.
+pub trait Batch {
+    fn rows(&self) -> Result<Vec<Value>>;
+
+    fn params(&self) -> Vec<Params>;
+}
+
+struct Rows<'a, T> {
+    verify: &'a Verify,
+    items: &'a [T],
+}
+
+impl<T: Row> Batch for Rows<'_, T> {
+    fn rows(&self) -> Result<Vec<Value>> {
+        Ok(self
+            .items
+            .iter()
+            .map(serde_json::to_value)
+            .collect::<serde_json::Result<_>>()?)
+    }
+
+    fn params(&self) -> Vec<Params> {
+        self.items
+            .iter()
+            .map(|item| item.params(self.verify))
+            .collect()
+    }
+}
+
+pub trait Sink: Send + Sync {
+    fn write(&self, verify: &Verify, batch: &dyn Batch) -> Result<()>;
+}
+
+pub static SINK: LazyLock<Box<dyn Sink>> = LazyLock::new(|| sink());
+
+fn sink() -> Box<dyn Sink> {
+    let sink = &config().sink;
+    match sink.kind {
+        SinkKind::Mysql => Box::new(MysqlSink),
+        SinkKind::Jsonl => Box::new(FileSink::new(&sink.dir, FileFormat::Jsonl)),
+        SinkKind::Csv => Box::new(FileSink::new(&sink.dir, FileFormat::Csv)),
+        #[cfg(test)]
+        SinkKind::Memory => Box::new(MemorySink::default()),
+        SinkKind::Diff => Box::new(DiffSink),
+    }
+}
+
+pub async fn write<T: Row + Send + 'static>(verify: &Verify, items: Vec<T>) -> Result<()> {
+    if items.is_empty() {
+        return Ok(());
+    }
+    let db = table(verify)?.db;
+    let verify = verify.clone();
+    blocking(db, move || {
+        SINK.write(
+            &verify,
+            &Rows {
+                verify: &verify,
+                items: &items,
+            },
+        )
+    })
+    .await
+}
+
+// Rows of one media and category land together, e.g. `2_adgroup_v3`.
+fn topic(verify: &Verify) -> String {
+    format!("{}_{}", verify.media_id, verify.cate)
+}
+
+// Where the rows of a media and category are stored and the statement that upserts them.
+pub struct Table {
+    pub media_id: u8,
+    pub cate: Category,
+    pub name: &'static str,
+    pub db: Db,
+    pub sql: &'static str,
+}
+
+const TABLES: &[Table] = &[
+    // Baidu
+    Table {
+        media_id: 1,
+        cate: Category::Account,
+        name: "synads_baidu_account",
+        db: Db::Tidb,
+        sql: BAIDU_ACCOUNT_SQL,
+    },
+    Table {
+        media_id: 1,
+        cate: Category::Campaign,
+        name: "synads_baidu_campaign",
+        db: Db::Tidb,
+        sql: BAIDU_CAMPAIGN_SQL,
+    },
+    Table {
+        media_id: 1,
+        cate: Category::Adgroup,
+        name: "synads_baidu_adgroup",
+        db: Db::Tidb,
+        sql: BAIDU_ADGROUP_SQL,
+    },
+    Table {
+        media_id: 1,
+        cate: Category::Keyword,
+        name: "synads_baidu_keyword",
+        db: Db::Tidb,
+        sql: BAIDU_KEYWORD_SQL,
+    },
+    Table {
+        media_id: 1,
+        cate: Category::Creative,
+        name: "synads_baidu_creative",
+        db: Db::Tidb,
+        sql: BAIDU_CREATIVE_SQL,
+    },
+    Table {
+        media_id: 1,
+        cate: Category::AccountDailyReport,
+        name: "synrpt_baidu_account_daily",
+        db: Db::Tidb,
+        sql: BAIDU_ACCOUNT_DAILY_REPORT_SQL,
+    },
+    Table {
+        media_id: 1,
+        cate: Category::KeywordDailyReport,
+        name: "synrpt_baidu_keyword_daily",
+        db: Db::Tidb,
+        sql: BAIDU_KEYWORD_DAILY_REPORT_SQL,
+    },
+    // GDT
+    Table {
+        media_id: 2,
+        cate: Category::AdgroupV3,
+        name: "synads_gdt_adgroup_v3",
+        db: Db::Tidb,
+        sql: GDT_ADGROUP_SQL_V3,
+    },
+    Table {
+        media_id: 2,
+        cate: Category::DynamicCreativeV3,
+        name: "synads_gdt_dynamic_creative_v3",
+        db: Db::Tidb,
+        sql: GDT_DYNAMIC_CREATIVE_SQL_V3,
+    },
+    Table {
+        media_id: 2,
+        cate: Category::AdgroupDailyRequestPartV3,
+        name: "synrpt_gdt_adgroup_daily_request_part_v3",
+        db: Db::Tidb,
+        sql: GDT_ADGROUP_DAILY_SQL_TL_REQUEST_V3,
+    },
+    Table {
+        media_id: 2,
+        cate: Category::AdvertiserReportingPartV3,
+        name: "synrpt_gdt_advertiser_reporting_part_v3",
+        db: Db::Tidb,
+        sql: GDT_ACCOUNT_HOURLY_SQL_TL_REPORTING_V3,
+    },
+    Table {
+        media_id: 2,
+        cate: Category::DynamicCreativeDailyRequestPartV3,
+        name: "Synrpt_Gdt_Dynamic_Creative_Daily_V3",
+        db: Db::Tidb,
+        sql: GDT_DYNAMIC_CREATIVE_DAILY_SQL_TL_REQUEST_V3,
+    },
+    Table {
+        media_id: 2,
+        cate: Category::AdvertiserDailyReportingPartV3,
+        name: "synrpt_gdt_advertiser_daily_reporting_part_v3",
+        db: Db::Tidb,
+        sql: GDT_ACCOUNT_DAILY_SQL_TL_REPORTING_V3,
+    },
+    Table {
+        media_id: 2,
+        cate: Category::Advertiser,
+        name: "gdt_advertiser",
+        db: Db::Core,
+        sql: GDT_ADVERTISER_SQL,
+    },
+    Table {
+        media_id: 2,
+        cate: Category::Target,
+        name: "gdt_ad_targeting",
+        db: Db::Tidb,
+        sql: GDT_TARGETING_SQL,
+    },
+    Table {
+        media_id: 2,
+        cate: Category::Audience,
+        name: "tx_audience_simple",
+        db: Db::Tidb,
+        sql: GDT_AUDIENCE_SQL,
+    },
+    Table {
+        media_id: 2,
+        cate: Category::Balance,
+        name: "synrpt_gdt_advertiser_ab",
+        db: Db::Tidb,
+        sql: GDT_BALANCE_SQL,
+    },
+    // TT
+    Table {
+        media_id: 4,
+        cate: Category::Project,
+        name: "synads_tt_project",
+        db: Db::Tidb,
+        sql: TT_PROJECT_SQL,
+    },
+    Table {
+        media_id: 4,
+        cate: Category::Promotion,
+        name: "synads_tt_promotion",
+        db: Db::Tidb,
+        sql: TT_PROMOTION_SQL,
+    },
+    Table {
+        media_id: 4,
+        cate: Category::AdvertiserHourlyReport,
+        name: "synrpt_tt_advertiser_v2",
+        db: Db::Tidb,
+        sql: TT_ADVERTISER_HOURLY_REPORT_SQL,
+    },
+    Table {
+        media_id: 4,
+        cate: Category::ProjectHourlyReport,
+        name: "synrpt_tt_project",
+        db: Db::Tidb,
+        sql: TT_PROJECT_HOURLY_REPORT_SQL,
+    },
+    Table {
+        media_id: 4,
+        cate: Category::PromotionHourlyReport,
+        name: "synrpt_tt_promotion",
+        db: Db::Tidb,
+        sql: TT_PROMOTION_HOURLY_REPORT_SQL,
+    },
+    Table {
+        media_id: 4,
+        cate: Category::Account,
+        name: "tt_advertiser",
+        db: Db::Core,
+        sql: TT_ADVERTISER_SQL,
+    },
+    Table {
+        media_id: 4,
+        cate: Category::Balance,
+        name: "synrpt_tt_advertiser_ab",
+        db: Db::Tidb,
+        sql: TT_BALANCE_SQL,
+    },
+    Table {
+        media_id: 4,
+        cate: Category::Budget,
+        name: "tt_advertiser_budget",
+        db: Db::Tidb,
+        sql: TT_BUDGET_SQL,
+    },
+    Table {
+        media_id: 4,
+        cate: Category::RtaInfo,
+        name: "synads_tt_rta_info",
+        db: Db::Tidb,
+        sql: TT_RTA_INFO_SQL,
+    },
+    // KS
+    Table {
+        media_id: 5,
+        cate: Category::Campaign,
+        name: "synads_ks_campaign",
+        db: Db::Tidb,
+        sql: KS_CAMPAIGN_SQL,
+    },
+    Table {
+        media_id: 5,
+        cate: Category::Unit,
+        name: "synads_ks_unit",
+        db: Db::Tidb,
+        sql: KS_UNIT_SQL,
+    },
+    Table {
+        media_id: 5,
+        cate: Category::Creative,
+        name: "synads_ks_creative",
+        db: Db::Tidb,
+        sql: KS_CREATIVE_SQL,
+    },
+    Table {
+        media_id: 5,
+        cate: Category::AccountHourlyReport,
+        name: "synrpt_ks_account_hourly",
+        db: Db::Tidb,
+        sql: KS_ACCOUNT_HOURLY_REPORT_SQL,
+    },
+    Table {
+        media_id: 5,
+        cate: Category::UnitHourlyReport,
+        name: "synrpt_ks_unit_hourly",
+        db: Db::Tidb,
+        sql: KS_UNIT_HOURLY_REPORT_SQL,
+    },
+    Table {
+        media_id: 5,
+        cate: Category::Balance,
+        name: "synrpt_ks_advertiser_ab",
+        db: Db::Tidb,
+        sql: KS_BALANCE_SQL,
+    },
+];
+
+pub fn table(verify: &Verify) -> Result<&'static Table> {
+    TABLES
+        .iter()
+        .find(|table| table.media_id == verify.media_id && table.cate == verify.cate)
+        .ok_or(Error::Unsupported {
+            media_id: verify.media_id,
+            cate: verify.cate,
+        })
+}
+
+// The production upserts against TiDB and the core database, the only place their sql runs.
+pub struct MysqlSink;
+
+impl Sink for MysqlSink {
+    fn write(&self, verify: &Verify, batch: &dyn Batch) -> Result<()> {
+        let table = table(verify)?;
+        let mut con = match table.db {
+            Db::Tidb => TIDB_POOL.get_conn()?,
+            Db::Core => CORE_POOL.get_conn()?,
+        };
+        upsert::exec(&mut con, table.sql, batch.params())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FileFormat {
+    Jsonl,
+    Csv,
+}
+
+// Appends rows to `<dir>/<media_id>_<category>.jsonl|csv`, each tagged with its account.
+pub struct FileSink {
+    dir: PathBuf,
+    format: FileFormat,
+    lock: Mutex<()>,
+}
+
+impl FileSink {
+    pub fn new(dir: &Path, format: FileFormat) -> Self {
+        FileSink {
+            dir: dir.to_path_buf(),
+            format,
+            lock: Mutex::new(()),
+        }
+    }
+
+    fn path(&self, verify: &Verify) -> PathBuf {
+        let ext = match self.format {
+            FileFormat::Jsonl => "jsonl",
+            FileFormat::Csv => "csv",
+        };
+        self.dir.join(format!("{}.{}", topic(verify), ext))
+    }
+}
+
+impl Sink for FileSink {
+    fn write(&self, verify: &Verify, batch: &dyn Batch) -> Result<()> {
+        let rows = batch.rows()?;
+        let _guard = self.lock.lock().unwrap_or_else(|e| e.into_inner());
+        fs::create_dir_all(&self.dir)?;
+        let path = self.path(verify);
+        let mut out = String::new();
+        match self.format {
+            FileFormat::Jsonl => {
+                for row in rows {
+                    out.push_str(
+                        &json!({ "account_id": verify.account_id, "row": row }).to_string(),
+                    );
+                    out.push('\n');
+                }
+            }
+            FileFormat::Csv => {
+                let header = match csv_header(&path)? {
+                    Some(header) => header,
+                    None => {
+                        let mut header = vec![String::from("account_id")];
+                        for row in &rows {
+                            if let Value::Object(map) = row {
+                                for key in map.keys() {
+                                    if !header.contains(key) {
+                                        header.push(key.clone());
+                                    }
+                                }
+                            }
+                        }
+                        out.push_str(
+                            &header
+                                .iter()
+                                .map(|c| csv_field(c))
+                                .collect::<Vec<_>>()
+                                .join(","),
+                        );
+                        out.push('\n');
+                        header
+                    }
+                };
+                // Columns are fixed by the first batch written to a file, later keys are dropped.
+                for row in rows {
+                    let fields = header
+                        .iter()
+                        .map(|column| match (column.as_str(), row.get(column)) {
+                            ("account_id", _) => verify.account_id.to_string(),
+                            (_, Some(Value::String(s))) => csv_field(s),
+                            (_, Some(Value::Null)) | (_, None) => String::new(),
+                            (_, Some(value)) => csv_field(&value.to_string()),
+                        })
+                        .collect::<Vec<_>>();
+                    out.push_str(&fields.join(","));
+                    out.push('\n');
+                }
+            }
+        }
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)?
+            .write_all(out.as_bytes())?;
+        Ok(())
+    }
+}
+
+fn csv_header(path: &Path) -> Result<Option<Vec<String>>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let mut line = String::new();
+    BufReader::new(fs::File::open(path)?).read_line(&mut line)?;
+    if line.trim().is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(
+        line.trim_end()
+            .split(',')
+            .map(|c| c.trim_matches('"').replace("\"\"", "\""))
+            .collect(),
+    ))
+}
+
+fn csv_field(raw: &str) -> String {
+    if raw.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", raw.replace('"', "\"\""))
+    } else {
+        raw.to_string()
+    }
+}
+
+// Keeps rows in process, keyed by `<media_id>_<category>`, for the tests to read back.
+#[cfg(test)]
+#[derive(Default)]
+pub struct MemorySink {
+    rows: Mutex<HashMap<String, Vec<Value>>>,
+}
+
+#[cfg(test)]
+impl MemorySink {
+    // The rows held for a topic, e.g. `2_adgroup_v3`.
+    pub fn rows(&self, topic: &str) -> Vec<Value> {
+        self.rows
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(topic)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    // Hands over every held row and starts empty again.
+    pub fn take(&self) -> HashMap<String, Vec<Value>> {
+        std::mem::take(&mut *self.rows.lock().unwrap_or_else(|e| e.into_inner()))
+    }
+}
+
+#[cfg(test)]
+impl Sink for MemorySink {
+    fn write(&self, verify: &Verify, batch: &dyn Batch) -> Result<()> {
+        let rows = batch.rows()?;
+        let mut held = self.rows.lock().unwrap_or_else(|e| e.into_inner());
+        held.entry(topic(verify)).or_default().extend(rows);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mysql::params;
+
+    #[derive(Serialize)]
+    struct Campaign {
+        campaign_id: u64,
+        budget: f64,
+    }
+
+    impl Row for Campaign {
+        fn params(&self, verify: &Verify) -> Params {
+            params! {
+                "advertiser_id" => verify.account_id,
+                "campaign_id" => self.campaign_id,
+                "day_budget" => self.budget * 1000.0,
+            }
+        }
+    }
+
+    fn verify() -> Verify {
+        serde_json::from_value(json!({
+            "id": "v-1",
+            "media_id": 5,
+            "account_id": 42,
+            "cate": "campaign",
+            "url": "https://ad.e.kuaishou.com/rest/openapi/v1/campaign/list",
+        }))
+        .unwrap()
+    }
+
+    fn campaigns() -> Vec<Campaign> {
+        vec![
+            Campaign {
+                campaign_id: 1,
+                budget: 1.5,
+            },
+            Campaign {
+                campaign_id: 2,
+                budget: 0.0,
+            },
+        ]
+    }
+
+    #[test]
+    fn rows_carry_the_verify_into_params() {
+        let verify = verify();
+        let items = campaigns();
+        let rows = Rows {
+            verify: &verify,
+            items: &items,
+        };
+        let Params::Named(params) = &rows.params()[0] else {
+            panic!("expected named params");
+        };
+        assert_eq!(params[b"advertiser_id".as_slice()], mysql::Value::UInt(42));
+        assert_eq!(
+            params[b"day_budget".as_slice()],
+            mysql::Value::Double(1500.0)
+        );
+    }
+
+    #[test]
+    fn memory_sink_keeps_rows_per_topic() {
+        let verify = verify();
+        let items = campaigns();
+        let sink = MemorySink::default();
+        let rows = Rows {
+            verify: &verify,
+            items: &items,
+        };
+        sink.write(&verify, &rows).unwrap();
+        sink.write(&verify, &rows).unwrap();
+        let held = sink.rows("5_campaign");
+        assert_eq!(held.len(), 4);
+        assert_eq!(held[0], json!({ "campaign_id": 1, "budget": 1.5 }));
+        assert!(sink.rows("2_adgroup_v3").is_empty());
+
+        let taken = sink.take();
+        assert_eq!(taken["5_campaign"].len(), 4);
+        assert!(sink.rows("5_campaign").is_empty());
+    }
+
+    #[test]
+    fn every_table_upserts_its_own_media_and_category() {
+        let mut seen = std::collections::HashSet::new();
+        for table in TABLES {
+            assert!(seen.insert((table.media_id, table.cate)));
+            assert!(!table.sql.is_empty());
+        }
+    }
+}
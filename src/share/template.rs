@@ -0,0 +1,341 @@
+use mysql::{DriverError, Params, Value};
+use std::collections::HashMap;
+
+// A single-row `INSERT ... VALUES (...) ON DUPLICATE KEY UPDATE ...` with named params, rewritten
+// so the value tuple can be repeated: `row` holds `?` in place of every param, in `names` order,
+// and the update clause reads the inserted values through `VALUES(col)`. Kept free of the rest of
+// the crate so `benches/upsert.rs` runs the same statements.
+pub struct Template {
+    head: String,
+    row: String,
+    tail: String,
+    names: Vec<Vec<u8>>,
+    columns: Vec<(String, String)>,
+}
+
+impl Template {
+    pub fn parse(sql: &str) -> Option<Template> {
+        const VALUES: &str = " VALUES ";
+        let at = sql.to_ascii_uppercase().find(VALUES)?;
+        let (head, rest) = sql.split_at(at + VALUES.len());
+        let columns = split(&head[head.find('(')? + 1..head.rfind(')')?])
+            .into_iter()
+            .map(|column| column.trim().trim_matches('`'))
+            .collect::<Vec<_>>();
+        let rest = rest.trim_start();
+        let end = closing(rest)?;
+        let (tuple, tail) = rest.split_at(end + 1);
+        let entries = split(&tuple[1..end]);
+        if entries.len() != columns.len() {
+            return None;
+        }
+        let mut names = Vec::new();
+        let row = replace_params(tuple, |name| {
+            names.push(name.as_bytes().to_vec());
+            Some(String::from("?"))
+        })?;
+        // Only a param that is the whole value of its column can be read back through VALUES().
+        let direct = entries
+            .iter()
+            .zip(&columns)
+            .filter_map(|(entry, column)| {
+                let name = entry.trim().strip_prefix(':')?;
+                name.bytes().all(is_ident).then_some((name, *column))
+            })
+            .collect::<Vec<_>>();
+        let inserted = direct.iter().copied().collect::<HashMap<_, _>>();
+        let tail = replace_params(tail, |name| {
+            inserted
+                .get(name)
+                .map(|column| format!("VALUES(`{}`)", column))
+        })?;
+        Some(Template {
+            head: String::from(head),
+            row,
+            tail,
+            names,
+            columns: direct
+                .into_iter()
+                .map(|(name, column)| (String::from(column), String::from(name)))
+                .collect(),
+        })
+    }
+
+    // The (column, param) pairs whose value is a param as is, in column order.
+    pub fn columns(&self) -> &[(String, String)] {
+        &self.columns
+    }
+
+    // The column a param is stored in, or the param itself when it only feeds an expression.
+    pub fn column<'a>(&'a self, param: &'a str) -> &'a str {
+        self.columns
+            .iter()
+            .find(|(_, name)| name == param)
+            .map_or(param, |(column, _)| column)
+    }
+
+    pub fn values(&self, params: Params) -> Result<Vec<Value>, DriverError> {
+        match params {
+            Params::Named(map) => self
+                .names
+                .iter()
+                .map(|name| {
+                    map.get(name).cloned().ok_or_else(|| {
+                        DriverError::MissingNamedParameter(
+                            String::from_utf8_lossy(name).into_owned(),
+                        )
+                    })
+                })
+                .collect(),
+            Params::Positional(values) if values.len() == self.names.len() => Ok(values),
+            Params::Empty if self.names.is_empty() => Ok(Vec::new()),
+            Params::Positional(values) => Err(DriverError::MismatchedStmtParams(
+                self.names.len() as u16,
+                values.len(),
+            )),
+            Params::Empty => Err(DriverError::MismatchedStmtParams(
+                self.names.len() as u16,
+                0,
+            )),
+        }
+    }
+
+    // Splits the rows into (row count, flattened values) chunks. A chunk ends before it would pass
+    // `max_placeholders` or `max_packet_bytes`, so a page of wide GDT rows takes a few statements
+    // while narrow ones fit in one. A single row over the packet budget still goes on its own.
+    pub fn chunks(
+        &self,
+        rows: Vec<Vec<Value>>,
+        max_placeholders: usize,
+        max_packet_bytes: usize,
+    ) -> Vec<(usize, Vec<Value>)> {
+        let max_rows = (max_placeholders / self.names.len().max(1)).max(1);
+        let fixed = self.head.len() + self.tail.len();
+        let mut chunks = Vec::new();
+        let (mut count, mut bytes, mut values) = (0, fixed, Vec::new());
+        for row in rows {
+            let size = self.row.len() + 2 + row.iter().map(value_size).sum::<usize>();
+            if count > 0 && (count == max_rows || bytes + size > max_packet_bytes) {
+                chunks.push((count, std::mem::take(&mut values)));
+                (count, bytes) = (0, fixed);
+            }
+            count += 1;
+            bytes += size;
+            values.extend(row);
+        }
+        if count > 0 {
+            chunks.push((count, values));
+        }
+        chunks
+    }
+
+    pub fn statement(&self, count: usize) -> String {
+        let mut sql =
+            String::with_capacity(self.head.len() + (self.row.len() + 2) * count + self.tail.len());
+        sql.push_str(&self.head);
+        for i in 0..count {
+            if i > 0 {
+                sql.push_str(", ");
+            }
+            sql.push_str(&self.row);
+        }
+        sql.push_str(&self.tail);
+        sql
+    }
+}
+
+// Rough size of a value in the execute packet.
+fn value_size(value: &Value) -> usize {
+    match value {
+        Value::Bytes(bytes) => bytes.len() + 9,
+        _ => 9,
+    }
+}
+
+fn is_ident(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b == b'_'
+}
+
+// Index of the parenthesis closing the one `text` starts with, skipping quoted literals.
+fn closing(text: &str) -> Option<usize> {
+    if !text.starts_with('(') {
+        return None;
+    }
+    let mut depth = 0;
+    let mut quote = None;
+    for (i, c) in text.char_indices() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '\'' | '"' | '`') => quote = Some(c),
+            (None, '(') => depth += 1,
+            (None, ')') => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+// Splits on the commas outside of parentheses and quoted literals.
+fn split(text: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0;
+    let mut quote = None;
+    let mut start = 0;
+    for (i, c) in text.char_indices() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '\'' | '"' | '`') => quote = Some(c),
+            (None, '(') => depth += 1,
+            (None, ')') => depth -= 1,
+            (None, ',') if depth == 0 => {
+                parts.push(&text[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&text[start..]);
+    parts
+}
+
+// Replaces every `:name` outside of quoted literals with what `f` returns for it, or gives up when
+// `f` has nothing for a name.
+fn replace_params(text: &str, mut f: impl FnMut(&str) -> Option<String>) -> Option<String> {
+    let bytes = text.as_bytes();
+    let mut out = String::with_capacity(text.len());
+    let mut quote = None;
+    let mut start = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        let b = bytes[i];
+        match quote {
+            Some(q) if b == q => quote = None,
+            Some(_) => {}
+            None if matches!(b, b'\'' | b'"' | b'`') => quote = Some(b),
+            None if b == b':' && bytes.get(i + 1).is_some_and(|&n| is_ident(n)) => {
+                let end = (i + 1..bytes.len())
+                    .find(|&j| !is_ident(bytes[j]))
+                    .unwrap_or(bytes.len());
+                out.push_str(&text[start..i]);
+                out.push_str(&f(&text[i + 1..end])?);
+                start = end;
+                i = end;
+                continue;
+            }
+            None => {}
+        }
+        i += 1;
+    }
+    out.push_str(&text[start..]);
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SQL: &str = "INSERT INTO `t` (`id`, `name`, `cost`, `note`, `sync_time`) \
+        VALUES (:id, :name, :cost / 100, 'a:b', NOW()) \
+        ON DUPLICATE KEY UPDATE `name` = :name, `sync_time` = NOW()";
+
+    fn row(id: i64) -> Vec<Value> {
+        vec![Value::Int(id), Value::from("x"), Value::Int(1)]
+    }
+
+    #[test]
+    fn parse_rewrites_params_and_the_update_clause() {
+        let template = Template::parse(SQL).unwrap();
+        assert_eq!(
+            template.names,
+            vec![b"id".to_vec(), b"name".to_vec(), b"cost".to_vec()]
+        );
+        assert_eq!(template.row, "(?, ?, ? / 100, 'a:b', NOW())");
+        assert_eq!(
+            template.tail,
+            " ON DUPLICATE KEY UPDATE `name` = VALUES(`name`), `sync_time` = NOW()"
+        );
+        assert_eq!(
+            template.columns(),
+            [
+                (String::from("id"), String::from("id")),
+                (String::from("name"), String::from("name"))
+            ]
+        );
+        assert_eq!(template.column("name"), "name");
+        assert_eq!(template.column("cost"), "cost");
+    }
+
+    #[test]
+    fn parse_gives_up_on_what_it_can_not_rewrite() {
+        assert!(Template::parse("UPDATE `t` SET `name` = :name WHERE `id` = :id").is_none());
+        assert!(
+            Template::parse(
+                "INSERT INTO `t` (`id`, `cost`) VALUES (:id, :cost / 100) \
+                 ON DUPLICATE KEY UPDATE `cost` = :cost / 100"
+            )
+            .is_none()
+        );
+        assert!(Template::parse("INSERT INTO `t` (`id`, `name`) VALUES (:id)").is_none());
+    }
+
+    #[test]
+    fn statement_repeats_the_row() {
+        let template = Template::parse(SQL).unwrap();
+        assert_eq!(
+            template.statement(2),
+            "INSERT INTO `t` (`id`, `name`, `cost`, `note`, `sync_time`) VALUES \
+             (?, ?, ? / 100, 'a:b', NOW()), (?, ?, ? / 100, 'a:b', NOW()) \
+             ON DUPLICATE KEY UPDATE `name` = VALUES(`name`), `sync_time` = NOW()"
+        );
+    }
+
+    #[test]
+    fn chunks_stay_under_the_placeholder_budget() {
+        let template = Template::parse(SQL).unwrap();
+        let rows = (0..5).map(row).collect();
+        let chunks = template.chunks(rows, 6, 4 << 20);
+        assert_eq!(
+            chunks.iter().map(|(count, _)| *count).collect::<Vec<_>>(),
+            vec![2, 2, 1]
+        );
+        assert_eq!(chunks[0].1.len(), 6);
+        assert_eq!(chunks[2].1, row(4));
+    }
+
+    #[test]
+    fn chunks_stay_under_the_packet_budget() {
+        let template = Template::parse(SQL).unwrap();
+        let rows = (0..3).map(row).collect();
+        let chunks = template.chunks(rows, 65535, 1);
+        assert_eq!(
+            chunks.iter().map(|(count, _)| *count).collect::<Vec<_>>(),
+            vec![1, 1, 1]
+        );
+    }
+
+    #[test]
+    fn values_follow_the_param_order() {
+        let template = Template::parse(SQL).unwrap();
+        let params = Params::from(vec![
+            ("cost", Value::Int(1)),
+            ("id", Value::Int(7)),
+            ("name", Value::from("x")),
+        ]);
+        assert_eq!(template.values(params).unwrap(), row(7));
+        assert!(matches!(
+            template.values(Params::from(vec![("id", Value::Int(7))])),
+            Err(DriverError::MissingNamedParameter(name)) if name == "name"
+        ));
+        assert!(matches!(
+            template.values(Params::Positional(vec![Value::Int(7)])),
+            Err(DriverError::MismatchedStmtParams(3, 1))
+        ));
+    }
+}
@@ -0,0 +1,390 @@
+use crate::model::base::{ApiRes, EmarTokenRt, OauthToken};
+use crate::model::error::{Error, Result};
+use crate::share::common::HTTP_CLIENT;
+use crate::share::config::{TokenProviderKind, config};
+use log::warn;
+use serde_json::json;
+use std::collections::HashMap;
+use std::future::Future;
+use std::path::Path;
+use std::pin::Pin;
+use std::sync::{Arc, LazyLock, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+const GDT_OAUTH_URL: &str = "https://api.e.qq.com/oauth/token";
+
+const TT_OAUTH_URL: &str = "https://ad.oceanengine.com/open_api/oauth2/refresh_token/";
+
+struct Cached {
+    token: String,
+    expires: Instant,
+}
+
+// One slot per (account_id, platform_id). Holding the async lock across the fetch makes
+// concurrent refreshes of the same account wait for a single request.
+type Slot = Arc<tokio::sync::Mutex<Option<Cached>>>;
+
+#[derive(Default)]
+struct Cache {
+    slots: Mutex<HashMap<(u64, u8), Slot>>,
+}
+
+static TOKENS: LazyLock<Cache> = LazyLock::new(Cache::default);
+
+impl Cache {
+    fn slot(&self, id: u64, platform_id: u8) -> Slot {
+        let mut slots = self.slots.lock().unwrap_or_else(|e| e.into_inner());
+        Arc::clone(slots.entry((id, platform_id)).or_default())
+    }
+
+    async fn token(
+        &self,
+        provider: &dyn TokenProvider,
+        ttl: Duration,
+        id: u64,
+        platform_id: u8,
+    ) -> Result<String> {
+        let slot = self.slot(id, platform_id);
+        let mut cached = slot.lock().await;
+        if let Some(Cached { token, expires }) = cached.as_ref()
+            && *expires > Instant::now()
+        {
+            return Ok(token.clone());
+        }
+        let token = provider.token(id, platform_id).await?;
+        *cached = Some(Cached {
+            token: token.clone(),
+            expires: Instant::now() + ttl,
+        });
+        Ok(token)
+    }
+
+    async fn invalidate(&self, id: u64, platform_id: u8, stale: &str) {
+        let slot = self.slot(id, platform_id);
+        let mut cached = slot.lock().await;
+        if cached.as_ref().is_some_and(|c| c.token == stale) {
+            *cached = None;
+        }
+    }
+}
+
+pub async fn account_token(id: u64, platform_id: u8) -> Result<String> {
+    let ttl = Duration::from_secs(config().token.ttl_secs);
+    TOKENS.token(provider(), ttl, id, platform_id).await
+}
+
+// Drops the cached token only if it is still the one the platform rejected, so a batch of
+// failing requests triggers one refresh instead of one per request.
+pub async fn invalidate(id: u64, platform_id: u8, stale: &str) {
+    TOKENS.invalidate(id, platform_id, stale).await;
+}
+
+type TokenFuture = Pin<Box<dyn Future<Output = Result<String>> + Send>>;
+
+pub trait TokenProvider: Send + Sync {
+    fn token(&self, account_id: u64, platform_id: u8) -> TokenFuture;
+}
+
+static PROVIDER: OnceLock<Box<dyn TokenProvider>> = OnceLock::new();
+
+// Builds the configured provider at startup, so an unreadable token file stops the service
+// instead of failing every account later.
+pub fn init() -> Result<()> {
+    let token = &config().token;
+    let provider: Box<dyn TokenProvider> = match token.provider {
+        TokenProviderKind::Http => Box::new(HttpProvider {
+            url: token.url.clone(),
+        }),
+        TokenProviderKind::File => Box::new(FileProvider {
+            tokens: load(&token.path)?,
+        }),
+        TokenProviderKind::Oauth => Box::new(OauthProvider {
+            refresh_tokens: Arc::new(tokio::sync::Mutex::new(load(&token.path)?)),
+        }),
+    };
+    PROVIDER
+        .set(provider)
+        .map_err(|_| Error::Config(String::from("token provider already initialized")))
+}
+
+fn provider() -> &'static dyn TokenProvider {
+    PROVIDER
+        .get()
+        .expect("Token Provider Not Initialized")
+        .as_ref()
+}
+
+fn key(account_id: u64, platform_id: u8) -> String {
+    format!("{}:{}", platform_id, account_id)
+}
+
+// The internal token service.
+pub struct HttpProvider {
+    url: String,
+}
+
+impl TokenProvider for HttpProvider {
+    fn token(&self, account_id: u64, platform_id: u8) -> TokenFuture {
+        let url = self.url.clone();
+        Box::pin(async move {
+            let res = HTTP_CLIENT
+                .get(&url)
+                .query(&[
+                    ("account_id", account_id),
+                    ("platform_id", platform_id.into()),
+                ])
+                .send()
+                .await?
+                .json::<EmarTokenRt>()
+                .await?;
+            match res {
+                EmarTokenRt {
+                    code: 0,
+                    access_token: Some(token),
+                    ..
+                } => Ok(token),
+                EmarTokenRt { code: 0, .. } => Err(Error::Token(format!(
+                    "token service returned no access_token for {}",
+                    key(account_id, platform_id)
+                ))),
+                EmarTokenRt { code, message, .. } => Err(Error::Token(format!(
+                    "token service code {} for {}: {}",
+                    code,
+                    key(account_id, platform_id),
+                    message.unwrap_or_default()
+                ))),
+            }
+        })
+    }
+}
+
+// Access tokens read once from `token.path`, for offline and test runs.
+pub struct FileProvider {
+    tokens: HashMap<String, String>,
+}
+
+impl TokenProvider for FileProvider {
+    fn token(&self, account_id: u64, platform_id: u8) -> TokenFuture {
+        let key = key(account_id, platform_id);
+        let rt = self.tokens.get(&key).cloned().ok_or_else(|| {
+            Error::Token(format!(
+                "no token for {} in {}",
+                key,
+                config().token.path.display()
+            ))
+        });
+        Box::pin(async move { rt })
+    }
+}
+
+// Exchanges the refresh tokens in `token.path` directly with GDT and TT. Both platforms rotate
+// the refresh token on use, so the new one is kept and written back to the file.
+// An exchange holds the async lock until its rotation is saved, so no two exchanges spend the same
+// refresh token and the file is never written with an older snapshot after a newer one.
+pub struct OauthProvider {
+    refresh_tokens: Arc<tokio::sync::Mutex<HashMap<String, String>>>,
+}
+
+impl TokenProvider for OauthProvider {
+    fn token(&self, account_id: u64, platform_id: u8) -> TokenFuture {
+        let refresh_tokens = Arc::clone(&self.refresh_tokens);
+        Box::pin(async move {
+            let key = key(account_id, platform_id);
+            let mut refresh_tokens = refresh_tokens.lock().await;
+            let refresh_token = refresh_tokens
+                .get(&key)
+                .cloned()
+                .ok_or_else(|| Error::Token(format!("no refresh token for {}", key)))?;
+            let oauth = &config().token.oauth;
+            let res = match platform_id {
+                // GDT
+                2 => {
+                    HTTP_CLIENT
+                        .get(GDT_OAUTH_URL)
+                        .query(&[
+                            ("client_id", oauth.gdt_client_id.to_string()),
+                            ("client_secret", oauth.gdt_client_secret.clone()),
+                            ("grant_type", String::from("refresh_token")),
+                            ("refresh_token", refresh_token),
+                        ])
+                        .send()
+                        .await?
+                        .json::<ApiRes<OauthToken>>()
+                        .await?
+                }
+                // TT
+                4 => {
+                    HTTP_CLIENT
+                        .post(TT_OAUTH_URL)
+                        .json(&json!({
+                            "app_id": oauth.tt_app_id,
+                            "secret": oauth.tt_secret,
+                            "grant_type": "refresh_token",
+                            "refresh_token": refresh_token,
+                        }))
+                        .send()
+                        .await?
+                        .json::<ApiRes<OauthToken>>()
+                        .await?
+                }
+                _ => {
+                    return Err(Error::Token(format!(
+                        "no oauth flow for media {}",
+                        platform_id
+                    )));
+                }
+            };
+            match res {
+                ApiRes {
+                    code: 0,
+                    data:
+                        Some(OauthToken {
+                            access_token,
+                            refresh_token,
+                        }),
+                    ..
+                } => {
+                    refresh_tokens.insert(key, refresh_token);
+                    if let Err(err) = save(&config().token.path, &refresh_tokens).await {
+                        warn!("Save Refresh Tokens Failed Reason {}", err);
+                    }
+                    Ok(access_token)
+                }
+                ApiRes {
+                    code,
+                    message,
+                    request_id,
+                    ..
+                } => Err(Error::Api {
+                    code,
+                    message: message.unwrap_or_default(),
+                    request_id,
+                }),
+            }
+        })
+    }
+}
+
+fn is_json(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "json")
+}
+
+fn load(path: &Path) -> Result<HashMap<String, String>> {
+    let raw = std::fs::read_to_string(path)
+        .map_err(|e| Error::Config(format!("token.path {}: {}", path.display(), e)))?;
+    if is_json(path) {
+        return serde_json::from_str(&raw)
+            .map_err(|e| Error::Config(format!("token.path {}: {}", path.display(), e)));
+    }
+    toml::from_str(&raw).map_err(|e| Error::Config(format!("token.path {}: {}", path.display(), e)))
+}
+
+async fn save(path: &Path, tokens: &HashMap<String, String>) -> Result<()> {
+    let raw = if is_json(path) {
+        serde_json::to_string_pretty(tokens)?
+    } else {
+        toml::to_string(tokens).map_err(|e| Error::Config(e.to_string()))?
+    };
+    let tmp = path.with_extension("tmp");
+    tokio::fs::write(&tmp, raw).await?;
+    tokio::fs::rename(&tmp, path).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn scratch(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("mav-token-{}-{}", std::process::id(), name))
+    }
+
+    #[tokio::test]
+    async fn save_then_load() {
+        for name in ["tokens.json", "tokens.toml"] {
+            let path = scratch(name);
+            let tokens = HashMap::from([(key(1001, 2), String::from("refresh"))]);
+            save(&path, &tokens).await.unwrap();
+            assert_eq!(load(&path).unwrap(), tokens);
+            std::fs::remove_file(&path).unwrap();
+        }
+    }
+
+    #[test]
+    fn missing_or_broken_file_is_an_error() {
+        let path = scratch("missing.json");
+        assert!(
+            matches!(load(&path), Err(Error::Config(reason)) if reason.contains("missing.json"))
+        );
+        let path = scratch("broken.json");
+        std::fs::write(&path, "{").unwrap();
+        assert!(matches!(load(&path), Err(Error::Config(_))));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    // Hands out "t1", "t2", ... and counts the calls, slowly enough for callers to overlap.
+    #[derive(Default)]
+    struct Counting {
+        calls: Arc<AtomicUsize>,
+    }
+
+    impl TokenProvider for Counting {
+        fn token(&self, _account_id: u64, _platform_id: u8) -> TokenFuture {
+            let calls = Arc::clone(&self.calls);
+            Box::pin(async move {
+                tokio::time::sleep(Duration::from_millis(20)).await;
+                let n = calls.fetch_add(1, Ordering::SeqCst) + 1;
+                Ok(format!("t{}", n))
+            })
+        }
+    }
+
+    impl Counting {
+        fn calls(&self) -> usize {
+            self.calls.load(Ordering::SeqCst)
+        }
+    }
+
+    const TTL: Duration = Duration::from_secs(60);
+
+    #[tokio::test]
+    async fn cached_until_the_ttl_runs_out() {
+        let (cache, provider) = (Cache::default(), Counting::default());
+        assert_eq!(cache.token(&provider, TTL, 1, 2).await.unwrap(), "t1");
+        assert_eq!(cache.token(&provider, TTL, 1, 2).await.unwrap(), "t1");
+        assert_eq!(provider.calls(), 1);
+        assert_eq!(cache.token(&provider, TTL, 1, 4).await.unwrap(), "t2");
+        let expired = Duration::ZERO;
+        assert_eq!(cache.token(&provider, expired, 3, 2).await.unwrap(), "t3");
+        assert_eq!(cache.token(&provider, expired, 3, 2).await.unwrap(), "t4");
+    }
+
+    #[tokio::test]
+    async fn concurrent_misses_share_one_refresh() {
+        let (cache, provider) = (Cache::default(), Counting::default());
+        let tokens = tokio::join!(
+            cache.token(&provider, TTL, 1, 2),
+            cache.token(&provider, TTL, 1, 2),
+            cache.token(&provider, TTL, 1, 2),
+            cache.token(&provider, TTL, 1, 2),
+        );
+        assert_eq!(provider.calls(), 1);
+        for token in [tokens.0, tokens.1, tokens.2, tokens.3] {
+            assert_eq!(token.unwrap(), "t1");
+        }
+    }
+
+    #[tokio::test]
+    async fn invalidate_only_drops_the_rejected_token() {
+        let (cache, provider) = (Cache::default(), Counting::default());
+        assert_eq!(cache.token(&provider, TTL, 1, 2).await.unwrap(), "t1");
+        cache.invalidate(1, 2, "t0").await;
+        assert_eq!(cache.token(&provider, TTL, 1, 2).await.unwrap(), "t1");
+        cache.invalidate(1, 2, "t1").await;
+        assert_eq!(cache.token(&provider, TTL, 1, 2).await.unwrap(), "t2");
+        cache.invalidate(1, 2, "t1").await;
+        assert_eq!(cache.token(&provider, TTL, 1, 2).await.unwrap(), "t2");
+        assert_eq!(provider.calls(), 2);
+    }
+}
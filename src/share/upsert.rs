@@ -0,0 +1,79 @@
+use crate::model::error::{Error, Result};
+use crate::share::config::config;
+use crate::share::template::Template;
+use log::{error, warn};
+use mysql::prelude::Queryable;
+use mysql::{DriverError, Params, PooledConn, TxOpts};
+use std::collections::HashMap;
+use std::sync::{Arc, LazyLock, Mutex};
+
+// Parsed once per sql, `None` when a template can not be rewritten and runs row by row.
+static TEMPLATES: LazyLock<Mutex<HashMap<String, Option<Arc<Template>>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+// Upserts a page of rows in one transaction, as few multi-row statements as the placeholder and
+// packet budgets of `[sink]` allow. A failed chunk rolls the whole page back.
+pub fn exec<P: Into<Params>>(
+    con: &mut PooledConn,
+    sql: &str,
+    rows: impl IntoIterator<Item = P>,
+) -> Result<()> {
+    let mut tx = con.start_transaction(TxOpts::default())?;
+    match template(sql) {
+        Some(template) => {
+            let rows = rows
+                .into_iter()
+                .enumerate()
+                .map(|(i, row)| values(&template, i, row.into()))
+                .collect::<Result<Vec<_>>>()?;
+            let sink = &config().sink;
+            for (count, values) in
+                template.chunks(rows, sink.max_placeholders, sink.max_packet_bytes)
+            {
+                tx.exec_drop(template.statement(count), values)?;
+            }
+        }
+        None => tx.exec_batch(sql, rows)?,
+    }
+    tx.commit()?;
+    Ok(())
+}
+
+// The (column, param) pairs of an upsert whose value is a param as is, in column order. Empty when
+// the statement is not an `INSERT ... VALUES` upsert.
+pub fn columns(sql: &str) -> Vec<(String, String)> {
+    template(sql)
+        .map(|template| template.columns().to_vec())
+        .unwrap_or_default()
+}
+
+fn template(sql: &str) -> Option<Arc<Template>> {
+    let mut templates = TEMPLATES.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(template) = templates.get(sql) {
+        return template.clone();
+    }
+    let template = Template::parse(sql).map(Arc::new);
+    if template.is_none() {
+        warn!(
+            "Upsert Falls Back To Row By Row For {}",
+            sql.chars().take(80).collect::<String>()
+        );
+    }
+    templates.insert(String::from(sql), template.clone());
+    template
+}
+
+// The values of the `i`th row of a page, a row that does not fit the statement names the column.
+fn values(template: &Template, i: usize, params: Params) -> Result<Vec<mysql::Value>> {
+    template.values(params).map_err(|err| {
+        match &err {
+            DriverError::MissingNamedParameter(name) => error!(
+                "Upsert Row {} Has No Value For Column {}",
+                i,
+                template.column(name)
+            ),
+            _ => error!("Upsert Row {} Failed Reason {}", i, err),
+        }
+        Error::Database(mysql::Error::DriverError(err))
+    })
+}
@@ -0,0 +1,26 @@
+use crate::model::error::Result;
+use crate::source::VerifySource;
+use tokio::sync::mpsc::{self, Receiver, Sender};
+
+pub struct ChannelSource {
+    rx: Receiver<String>,
+}
+
+pub fn channel(capacity: usize) -> (Sender<String>, ChannelSource) {
+    let (tx, rx) = mpsc::channel(capacity);
+    (tx, ChannelSource { rx })
+}
+
+impl VerifySource for ChannelSource {
+    async fn fetch(&mut self, max: usize) -> Result<Option<Vec<String>>> {
+        let mut items = Vec::with_capacity(max.min(200));
+        if self.rx.recv_many(&mut items, max).await == 0 {
+            return Ok(None);
+        }
+        Ok(Some(items))
+    }
+
+    async fn depth(&mut self) -> Result<Option<u64>> {
+        Ok(Some(self.rx.len() as u64))
+    }
+}
@@ -0,0 +1,39 @@
+use crate::model::error::Result;
+use crate::source::channel::{ChannelSource, channel};
+use log::error;
+use tokio::fs::File;
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
+
+const CHANNEL_CAPACITY: usize = 1000;
+
+pub async fn open(path: &str) -> Result<ChannelSource> {
+    let reader: Box<dyn AsyncRead + Unpin + Send> = if path == "-" {
+        Box::new(tokio::io::stdin())
+    } else {
+        Box::new(File::open(path).await?)
+    };
+    let (tx, source) = channel(CHANNEL_CAPACITY);
+    let path = path.to_string();
+    tokio::spawn(async move {
+        let mut lines = BufReader::new(reader).lines();
+        loop {
+            match lines.next_line().await {
+                Ok(Some(line)) => {
+                    let line = line.trim();
+                    if line.is_empty() {
+                        continue;
+                    }
+                    if tx.send(line.to_string()).await.is_err() {
+                        break;
+                    }
+                }
+                Ok(None) => break,
+                Err(e) => {
+                    error!("Failed To Read {}: {}", path, e);
+                    break;
+                }
+            }
+        }
+    });
+    Ok(source)
+}
@@ -0,0 +1,31 @@
+pub mod channel;
+pub mod file;
+pub mod redis_list;
+pub mod redis_reliable;
+pub mod redis_stream;
+
+use crate::model::error::Result;
+
+pub trait VerifySource {
+    async fn fetch(&mut self, max: usize) -> Result<Option<Vec<String>>>;
+
+    async fn commit(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    // Puts messages of the current batch that were never processed back and settles the rest of
+    // the batch. Returns how many went back, sources that cannot take messages back return 0.
+    async fn requeue(&mut self, _items: Vec<String>) -> Result<usize> {
+        Ok(0)
+    }
+
+    // Gives back what the source holds for this worker before the process exits.
+    async fn close(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    // Messages still waiting in the source, when it can tell.
+    async fn depth(&mut self) -> Result<Option<u64>> {
+        Ok(None)
+    }
+}
@@ -0,0 +1,51 @@
+use crate::model::error::Result;
+use crate::share::common::redis;
+use crate::source::VerifySource;
+use log::error;
+use redis::AsyncCommands;
+use redis::cluster_async::ClusterConnection;
+
+pub struct RedisListSource {
+    conn: ClusterConnection,
+    key: String,
+}
+
+impl RedisListSource {
+    pub async fn new(key: &str) -> Result<Self> {
+        Ok(RedisListSource {
+            conn: redis().await?,
+            key: key.to_string(),
+        })
+    }
+}
+
+impl VerifySource for RedisListSource {
+    async fn fetch(&mut self, max: usize) -> Result<Option<Vec<String>>> {
+        let mut items = Vec::with_capacity(200);
+
+        for _ in 0..max {
+            match self.conn.lpop(&self.key, None).await {
+                Ok(Some(msg)) => items.push(msg),
+                Ok(None) => break,
+                Err(e) => {
+                    error!("Redis Error: {}", e);
+                    break;
+                }
+            }
+        }
+
+        Ok(Some(items))
+    }
+
+    async fn requeue(&mut self, items: Vec<String>) -> Result<usize> {
+        if items.is_empty() {
+            return Ok(0);
+        }
+        let _: usize = self.conn.lpush(&self.key, &items).await?;
+        Ok(items.len())
+    }
+
+    async fn depth(&mut self) -> Result<Option<u64>> {
+        Ok(Some(self.conn.llen(&self.key).await?))
+    }
+}
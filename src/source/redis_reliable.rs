@@ -0,0 +1,238 @@
+use crate::model::error::{Error, Result};
+use crate::share::common::{redis, slot_prefix};
+use crate::share::config::SourceConfig;
+use crate::source::VerifySource;
+use chrono::Local;
+use log::{error, info, warn};
+use redis::cluster_async::ClusterConnection;
+use redis::{AsyncCommands, Direction, Script};
+use std::collections::HashMap;
+use std::sync::{LazyLock, OnceLock};
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tokio::time::interval;
+
+// Registers a worker unless another one with the same id sent a heartbeat within the timeout.
+static REGISTER_SCRIPT: LazyLock<Script> = LazyLock::new(|| {
+    Script::new(
+        r#"
+local beat = redis.call('HGET', KEYS[1], ARGV[1])
+if beat and tonumber(ARGV[2]) - tonumber(beat) < tonumber(ARGV[3]) then
+    return 0
+end
+redis.call('HSET', KEYS[1], ARGV[1], ARGV[2])
+return 1
+"#,
+    )
+});
+
+// Moves a message from the processing list back to the queue in one step, or not at all when the
+// reaper already took it back.
+static REQUEUE_SCRIPT: LazyLock<Script> = LazyLock::new(|| {
+    Script::new(
+        r#"
+if redis.call('LREM', KEYS[1], 1, ARGV[1]) == 0 then
+    return 0
+end
+redis.call('LPUSH', KEYS[2], ARGV[1])
+return 1
+"#,
+    )
+});
+
+// Processing list of the running source, messages are acked out of it one by one.
+static PROCESSING: OnceLock<String> = OnceLock::new();
+
+pub struct RedisReliableSource {
+    conn: ClusterConnection,
+    keys: Keys,
+    worker: String,
+    processing: String,
+    reaper: JoinHandle<()>,
+}
+
+#[derive(Clone)]
+struct Keys {
+    queue: String,
+    workers: String,
+    prefix: String,
+}
+
+impl Keys {
+    fn new(queue: &str) -> Self {
+        let prefix = slot_prefix(queue);
+        Keys {
+            queue: queue.to_string(),
+            workers: format!("{}:workers", prefix),
+            prefix,
+        }
+    }
+
+    fn processing(&self, worker: &str) -> String {
+        format!("{}:processing:{}", self.prefix, worker)
+    }
+}
+
+impl RedisReliableSource {
+    pub async fn new(queue: &str, source: &SourceConfig) -> Result<Self> {
+        let keys = Keys::new(queue);
+        let worker = source.consumer.clone();
+        let processing = keys.processing(&worker);
+        let mut conn = redis().await?;
+        let timeout = Duration::from_secs(source.visibility_timeout_secs);
+
+        // Two workers sharing an id would settle and recover each other's processing list.
+        let registered: i32 = REGISTER_SCRIPT
+            .key(&keys.workers)
+            .arg(&worker)
+            .arg(Local::now().timestamp_millis())
+            .arg(timeout.as_millis() as i64)
+            .invoke_async(&mut conn)
+            .await?;
+        if registered == 0 {
+            return Err(Error::Config(format!(
+                "source.consumer {} is already registered by a live worker",
+                worker
+            )));
+        }
+
+        let recovered = requeue(&mut conn, &keys.queue, &processing).await?;
+        if recovered > 0 {
+            info!("Recovered {} Unacked Messages Of {}", recovered, worker);
+        }
+
+        let reaper_keys = keys.clone();
+        let reaper_worker = worker.clone();
+        let reaper = tokio::spawn(async move {
+            let mut ticker = interval(timeout / 3);
+            loop {
+                ticker.tick().await;
+                if let Err(err) = reap(&reaper_keys, &reaper_worker, timeout).await {
+                    error!("Reaper Failed Reason {}", err);
+                }
+            }
+        });
+
+        let _ = PROCESSING.set(processing.clone());
+        Ok(RedisReliableSource {
+            conn,
+            keys,
+            worker,
+            processing,
+            reaper,
+        })
+    }
+}
+
+impl VerifySource for RedisReliableSource {
+    async fn fetch(&mut self, max: usize) -> Result<Option<Vec<String>>> {
+        let mut items = Vec::with_capacity(200);
+
+        for _ in 0..max {
+            let moved: redis::RedisResult<Option<String>> = self
+                .conn
+                .lmove(
+                    &self.keys.queue,
+                    &self.processing,
+                    Direction::Left,
+                    Direction::Right,
+                )
+                .await;
+            match moved {
+                Ok(Some(msg)) => items.push(msg),
+                Ok(None) => break,
+                Err(e) => {
+                    error!("Redis Error: {}", e);
+                    break;
+                }
+            }
+        }
+
+        Ok(Some(items))
+    }
+
+    // Messages leave the processing list through `ack` as their results are stored, so there is
+    // nothing left to settle for the batch as a whole.
+
+    // Unfinished messages are still in the processing list, each goes back to the queue in one
+    // atomic move so a crash half way can not leave it in both.
+    async fn requeue(&mut self, items: Vec<String>) -> Result<usize> {
+        let mut moved = 0;
+        for item in &items {
+            let one: usize = REQUEUE_SCRIPT
+                .key(&self.processing)
+                .key(&self.keys.queue)
+                .arg(item)
+                .invoke_async(&mut self.conn)
+                .await?;
+            moved += one;
+        }
+        Ok(moved)
+    }
+
+    async fn depth(&mut self) -> Result<Option<u64>> {
+        Ok(Some(self.conn.llen(&self.keys.queue).await?))
+    }
+
+    // Whatever was never acked goes back to the queue before the worker deregisters, so a
+    // restart under the same id is not refused until its heartbeat runs out.
+    async fn close(&mut self) -> Result<()> {
+        self.reaper.abort();
+        let moved = requeue(&mut self.conn, &self.keys.queue, &self.processing).await?;
+        if moved > 0 {
+            warn!("Requeued {} Unacked Messages Of {}", moved, self.worker);
+        }
+        let _: usize = self.conn.hdel(&self.keys.workers, &self.worker).await?;
+        Ok(())
+    }
+}
+
+// Takes a message out of the processing list once its result is stored. A no-op unless the
+// reliable source is running.
+pub async fn ack(conn: &mut ClusterConnection, raw: &str) -> Result<()> {
+    if let Some(processing) = PROCESSING.get()
+        && !raw.is_empty()
+    {
+        let _: usize = conn.lrem(processing, 1, raw).await?;
+    }
+    Ok(())
+}
+
+async fn heartbeat(conn: &mut ClusterConnection, keys: &Keys, worker: &str) -> Result<()> {
+    let _: usize = conn
+        .hset(&keys.workers, worker, Local::now().timestamp_millis())
+        .await?;
+    Ok(())
+}
+
+async fn requeue(conn: &mut ClusterConnection, queue: &str, processing: &str) -> Result<usize> {
+    let mut moved = 0;
+    loop {
+        let msg: Option<String> = conn
+            .lmove(processing, queue, Direction::Right, Direction::Left)
+            .await?;
+        if msg.is_none() {
+            return Ok(moved);
+        }
+        moved += 1;
+    }
+}
+
+async fn reap(keys: &Keys, worker: &str, timeout: Duration) -> Result<()> {
+    let mut conn = redis().await?;
+    heartbeat(&mut conn, keys, worker).await?;
+
+    let now = Local::now().timestamp_millis();
+    let workers: HashMap<String, i64> = conn.hgetall(&keys.workers).await?;
+    for (other, beat) in workers {
+        if other == worker || now - beat < timeout.as_millis() as i64 {
+            continue;
+        }
+        let moved = requeue(&mut conn, &keys.queue, &keys.processing(&other)).await?;
+        let _: usize = conn.hdel(&keys.workers, &other).await?;
+        if moved > 0 {
+            warn!("Requeued {} Messages From Dead Worker {}", moved, other);
+        }
+    }
+    Ok(())
+}
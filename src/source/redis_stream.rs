@@ -0,0 +1,167 @@
+use crate::model::error::Result;
+use crate::share::common::REDIS_CLIENT;
+use crate::share::config::SourceConfig;
+use crate::source::VerifySource;
+use log::{error, info, warn};
+use redis::AsyncCommands;
+use redis::cluster::ClusterConfig;
+use redis::cluster_async::ClusterConnection;
+use redis::streams::{
+    StreamAutoClaimOptions, StreamAutoClaimReply, StreamId, StreamReadOptions, StreamReadReply,
+};
+use std::time::{Duration, Instant};
+
+// The XAUTOCLAIM cursor that starts a pass over the pending entries and ends a finished one.
+const CLAIM_START: &str = "0-0";
+
+pub struct RedisStreamSource {
+    conn: ClusterConnection,
+    key: String,
+    group: String,
+    consumer: String,
+    field: String,
+    block_ms: usize,
+    min_idle: Duration,
+    claim_from: String,
+    claimed_at: Option<Instant>,
+    pending: Vec<String>,
+}
+
+impl RedisStreamSource {
+    pub async fn new(source: &SourceConfig) -> Result<Self> {
+        // XREADGROUP BLOCK would hold up every command multiplexed behind it, so the stream gets a
+        // connection of its own that waits longer than the block.
+        let config = ClusterConfig::new()
+            .set_response_timeout(Duration::from_millis(source.block_ms as u64 + 1000));
+        let mut conn = REDIS_CLIENT
+            .get_async_connection_with_config(config)
+            .await?;
+        let created: redis::RedisResult<()> = conn
+            .xgroup_create_mkstream(&source.stream_key, &source.group, "0")
+            .await;
+        match created {
+            Ok(()) => {}
+            Err(e) if e.code() == Some("BUSYGROUP") => {}
+            Err(e) => return Err(e.into()),
+        }
+        Ok(RedisStreamSource {
+            conn,
+            key: source.stream_key.clone(),
+            group: source.group.clone(),
+            consumer: source.consumer.clone(),
+            field: source.field.clone(),
+            block_ms: source.block_ms,
+            min_idle: Duration::from_secs(source.visibility_timeout_secs),
+            claim_from: String::from(CLAIM_START),
+            claimed_at: None,
+            pending: vec![],
+        })
+    }
+
+    // Takes over entries another consumer read and never acked, the batch of a crashed or
+    // redeployed instance, once they sat idle for `visibility_timeout_secs`. A pass walks the
+    // pending entries `max` at a time and the next one starts after another timeout.
+    async fn claim(&mut self, max: usize) -> Result<Vec<StreamId>> {
+        let due = self.claim_from != CLAIM_START
+            || self
+                .claimed_at
+                .is_none_or(|at| at.elapsed() >= self.min_idle);
+        if !due {
+            return Ok(vec![]);
+        }
+        let reply: StreamAutoClaimReply = self
+            .conn
+            .xautoclaim_options(
+                &self.key,
+                &self.group,
+                &self.consumer,
+                self.min_idle.as_millis() as u64,
+                &self.claim_from,
+                StreamAutoClaimOptions::default().count(max),
+            )
+            .await?;
+        if self.claim_from == CLAIM_START {
+            self.claimed_at = Some(Instant::now());
+        }
+        self.claim_from = reply.next_stream_id;
+        if !reply.claimed.is_empty() {
+            info!(
+                "Claimed {} Idle Entries Of Group {}",
+                reply.claimed.len(),
+                self.group
+            );
+        }
+        Ok(reply.claimed)
+    }
+}
+
+impl VerifySource for RedisStreamSource {
+    async fn fetch(&mut self, max: usize) -> Result<Option<Vec<String>>> {
+        let mut entries = self.claim(max).await?;
+        if entries.is_empty() {
+            let opts = StreamReadOptions::default()
+                .group(&self.group, &self.consumer)
+                .count(max)
+                .block(self.block_ms);
+            let reply: Option<StreamReadReply> =
+                self.conn.xread_options(&[&self.key], &[">"], &opts).await?;
+            entries = reply
+                .map(|r| r.keys)
+                .unwrap_or_default()
+                .into_iter()
+                .flat_map(|stream| stream.ids)
+                .collect();
+        }
+        let mut items = vec![];
+        for entry in entries {
+            match entry.get::<String>(&self.field) {
+                Some(msg) => items.push(msg),
+                None => error!("Stream Entry {} Missing Field {}", entry.id, self.field),
+            }
+            self.pending.push(entry.id);
+        }
+        Ok(Some(items))
+    }
+
+    // Unfinished messages are appended as new entries, so any consumer of the group picks them
+    // up, and the batch is acked.
+    async fn requeue(&mut self, items: Vec<String>) -> Result<usize> {
+        for item in &items {
+            let _: String = self
+                .conn
+                .xadd(&self.key, "*", &[(&self.field, item)])
+                .await?;
+        }
+        self.commit().await?;
+        Ok(items.len())
+    }
+
+    async fn commit(&mut self) -> Result<()> {
+        if !self.pending.is_empty() {
+            let _: usize = self
+                .conn
+                .xack(&self.key, &self.group, &self.pending)
+                .await?;
+            self.pending.clear();
+        }
+        Ok(())
+    }
+
+    // Leaves the group so it does not collect consumers of past deploys. Deleting a consumer
+    // drops its pending entries, so one with a batch still unacked stays for others to claim.
+    async fn close(&mut self) -> Result<()> {
+        if !self.pending.is_empty() {
+            warn!(
+                "Consumer {} Keeps {} Unacked Entries For Another Instance To Claim",
+                self.consumer,
+                self.pending.len()
+            );
+            return Ok(());
+        }
+        let _: usize = self
+            .conn
+            .xgroup_delconsumer(&self.key, &self.group, &self.consumer)
+            .await?;
+        Ok(())
+    }
+}